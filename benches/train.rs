@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use criterion::{criterion_group, criterion_main, Criterion as Bench};
+use dectree::{
+    dataset::{load_csv, load_dataset},
+    tree::{
+        train, Axis, Class, Criterion, DataPoint, DecisionTree, Hyperparameters, LabelledData,
+        SplitSearch,
+    },
+};
+
+// the row-based learner `train` used before the columnar rewrite, kept here as
+// the baseline: every candidate split clones the data points into new vectors
+mod rowwise {
+    use super::*;
+
+    fn split(c: &Criterion, data: &[(DataPoint, Class)]) -> (LabelledData, LabelledData) {
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+        for (dp, class) in data {
+            if dp[c.axis] < c.split {
+                left.push((dp.clone(), *class));
+            } else {
+                right.push((dp.clone(), *class));
+            }
+        }
+        (left, right)
+    }
+
+    fn gini_loss(classes: &[Class], vec: &[(DataPoint, Class)]) -> f64 {
+        let mut loss = 0f64;
+        for class in classes {
+            let p = 1f64 / (vec.len() as f64)
+                * (vec.iter().filter(|(_dp, cls)| cls == class).count() as f64);
+            loss += p * (1f64 - p);
+        }
+        loss
+    }
+
+    fn find_criterion(
+        axises: &[Axis],
+        classes: &[Class],
+        data: &[(DataPoint, Class)],
+    ) -> Criterion {
+        let mut min_impurity = f64::MAX;
+        let mut min_impurity_axis = axises[0];
+        let mut min_impurity_split = 0f64;
+        for axis in axises {
+            let mut max = f64::MIN;
+            let mut min = f64::MAX;
+            for (dp, _class) in data {
+                max = max.max(dp[axis]);
+                min = min.min(dp[axis]);
+            }
+            let step = (max - min) / 100.;
+            if step == 0. {
+                continue;
+            }
+            let mut split_criterion = min + step;
+            while split_criterion <= max {
+                let c = Criterion {
+                    axis: *axis,
+                    split: split_criterion,
+                    p_value: None,
+                };
+                let (left, right) = split(&c, data);
+                if left.is_empty() || right.is_empty() {
+                    split_criterion += step;
+                    continue;
+                }
+                let left_loss = gini_loss(classes, &left);
+                let right_loss = gini_loss(classes, &right);
+                let impurity = ((left.len() as f64) / (data.len() as f64)) * left_loss
+                    + ((right.len() as f64) / (data.len() as f64)) * right_loss;
+                if impurity < min_impurity {
+                    min_impurity = impurity;
+                    min_impurity_axis = *axis;
+                    min_impurity_split = split_criterion;
+                }
+                split_criterion += step;
+            }
+        }
+        Criterion {
+            axis: min_impurity_axis,
+            split: min_impurity_split,
+            p_value: None,
+        }
+    }
+
+    pub fn train(
+        axises: &[Axis],
+        classes: &[Class],
+        data: Vec<(DataPoint, Class)>,
+        hyperparameters: &Hyperparameters,
+        depth: usize,
+    ) -> DecisionTree {
+        if depth < hyperparameters.max_depth && data.len() > hyperparameters.min_elements_to_split {
+            let c = find_criterion(axises, classes, &data);
+            let (left, right) = split(&c, &data);
+            DecisionTree::Branch(
+                c,
+                Box::new(train(axises, classes, left, hyperparameters, depth + 1)),
+                Box::new(train(axises, classes, right, hyperparameters, depth + 1)),
+            )
+        } else {
+            let mut counter = HashMap::<Class, usize>::new();
+            for (_dp, class) in data {
+                *counter.entry(class).or_default() += 1;
+            }
+            DecisionTree::Leave(
+                *counter
+                    .iter()
+                    .max_by_key(|(_class, count)| *count)
+                    .unwrap()
+                    .0,
+            )
+        }
+    }
+}
+
+fn same_splits(a: &DecisionTree, b: &DecisionTree) -> bool {
+    match (a, b) {
+        (DecisionTree::Branch(a, a_left, a_right), DecisionTree::Branch(b, b_left, b_right)) => {
+            a.axis == b.axis
+                && a.split == b.split
+                && same_splits(a_left, b_left)
+                && same_splits(a_right, b_right)
+        }
+        (DecisionTree::Leave(_), DecisionTree::Leave(_)) => true,
+        _ => false,
+    }
+}
+
+fn bench_train(c: &mut Bench) {
+    let dataset = load_dataset(load_csv("rice".to_owned())).unwrap();
+    let data = dataset.labelled();
+    let hyperparameters = Hyperparameters::default();
+    let axises = &dataset.mapped_axis;
+    let classes = &dataset.mapped_class;
+
+    assert!(same_splits(
+        &train(axises, classes, data.clone(), &hyperparameters),
+        &rowwise::train(axises, classes, data.clone(), &hyperparameters, 0),
+    ));
+
+    let mut group = c.benchmark_group("train rice");
+    group.sample_size(10);
+    group.bench_function("columnar", |b| {
+        b.iter(|| train(axises, classes, data.clone(), &hyperparameters))
+    });
+    let histogram = Hyperparameters {
+        split_search: SplitSearch::Histogram { max_bins: 256 },
+        ..Hyperparameters::default()
+    };
+    group.bench_function("histogram", |b| {
+        b.iter(|| train(axises, classes, data.clone(), &histogram))
+    });
+    group.bench_function("rowwise", |b| {
+        b.iter(|| rowwise::train(axises, classes, data.clone(), &hyperparameters, 0))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_train);
+criterion_main!(benches);
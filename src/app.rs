@@ -0,0 +1,3301 @@
+use crate::calibration::{
+    auc, average_precision, brier_score, out_of_fold_probabilities, pr_curve, reliability,
+    roc_curve, Calibration, Calibrator, LeafProbabilities, ReliabilityBin,
+};
+use crate::codegen::{compile_tree, Language};
+use crate::dataset::{load_csv, load_dataset, DataSet};
+use crate::eval::{accuracy, mcnemar, CurveKind, CurvePoint, McNemar, Parameter};
+use crate::growth::{Growth, Step};
+use crate::histogram::MAX_BINS;
+use crate::i18n::{dataset_name, tr, Locale};
+use crate::isolation::anomaly_scores;
+use crate::rules::{
+    display_conditions, display_hyperplane, extract_rules, leaf_index, regions, Condition, Rule,
+};
+use crate::search::{Grid, SearchResult};
+use crate::session::{self, Session, SharedRun};
+use crate::stats::{class_counts, correlation, feature_histogram, summarize};
+use crate::tree::Class;
+use crate::tree::*;
+use crate::worker::{
+    post_curve, post_search, post_train, spawn_worker, CurveRequest, SearchRequest, TrainEvent,
+    TrainRequest,
+};
+
+use std::{collections::HashMap, iter::repeat_n, time::Duration};
+
+use js_sys::{Array, Object, Reflect};
+use leptos::logging::log;
+use leptos::*;
+use leptos_dom::helpers::{
+    get_property, request_animation_frame, set_interval_with_handle, set_property,
+    window_event_listener_untyped, IntervalHandle,
+};
+use leptos_meta::*;
+use stylers::style_str;
+use wasm_bindgen::{prelude::*, JsValue};
+use web_sys::{CanvasRenderingContext2d, Element, Worker};
+
+const RANDOM_SEED: u64 = 0;
+const RANDOM_SEARCH_SIZE: usize = 20;
+const SEARCH_FOLDS: usize = 5;
+// Chart.js' default dataset colours, so classes look the same in every chart
+const CLASS_COLORS: [&str; 7] = [
+    "54, 162, 235",
+    "255, 99, 132",
+    "255, 159, 64",
+    "255, 205, 86",
+    "75, 192, 192",
+    "153, 102, 255",
+    "201, 203, 207",
+];
+const MATRIX_CELL: f64 = 64.;
+const MATRIX_PADDING: f64 = 4.;
+const HISTOGRAM_BINS: usize = 20;
+const RELIABILITY_BINS: usize = 10;
+const MCNEMAR_SIGNIFICANCE: f64 = 0.05;
+// isolation forest scores from this on are flagged until the slider moves
+const ANOMALY_THRESHOLD: f64 = 0.6;
+
+#[component]
+pub fn App() -> impl IntoView {
+    provide_meta_context();
+    view! {
+        <ErrorBoundary fallback=|errors| {
+            view! {
+                <h1>"Uh oh! Something went wrong!"</h1>
+                <p>"Errors: "</p>
+                <ul>
+                    {move || {
+                        errors
+                            .get()
+                            .into_iter()
+                            .map(|(_, e)| view! { <li>{e.to_string()}</li> })
+                            .collect_view()
+                    }}
+
+                </ul>
+            }
+        }>
+            <Main />
+        </ErrorBoundary>
+    }
+}
+
+fn get(obj: &JsValue, prop: &str) -> JsValue {
+    Reflect::get(obj, &JsValue::from_str(prop)).unwrap()
+}
+
+fn set(obj: &JsValue, prop: &str, value: &JsValue) {
+    Reflect::set(obj, &JsValue::from_str(prop), value).unwrap();
+}
+
+#[wasm_bindgen(
+    inline_js = "export function new_chart(ctx, config) { return new Chart(ctx, config); }"
+)]
+extern "C" {
+    fn new_chart(ctx: &web_sys::HtmlElement, config: &Object) -> JsValue;
+}
+
+#[wasm_bindgen(inline_js = "export function update_chart(chart) { chart.update(); }")]
+extern "C" {
+    fn update_chart(chart: &JsValue);
+}
+
+#[wasm_bindgen(inline_js = "export function destroy_chart(chart) { chart.destroy(); }")]
+extern "C" {
+    fn destroy_chart(chart: &JsValue);
+}
+
+// shows the `tooltip` of a point if it has one, and the default label otherwise
+#[wasm_bindgen(
+    inline_js = "export function point_tooltip() { return (context) => context.raw.tooltip ?? `${context.dataset.label}: ${context.formattedValue}`; }"
+)]
+extern "C" {
+    fn point_tooltip() -> JsValue;
+}
+
+#[wasm_bindgen(
+    inline_js = "export function copy_text(text) { navigator.clipboard?.writeText(text); }"
+)]
+extern "C" {
+    fn copy_text(text: &str);
+}
+
+#[wasm_bindgen(inline_js = "export function toast_alert_ffi(alert) { alert.toast(); }")]
+extern "C" {
+    fn toast_alert_ffi(alert: &JsValue);
+}
+
+fn toast_alert(alert: NodeRef<html::Custom>) {
+    toast_alert_ffi(&(alert.get_untracked().unwrap().into_any()));
+}
+
+fn split_train_classify_dataset(
+    dataset: &DataSet,
+    seed: u64,
+) -> (LabelledData, Vec<DataPoint>, LabelledData) {
+    let (train_data, classify_data) = dataset.split(dataset.rows.len() / 5, seed);
+    let train_classify_data = train_data.iter().map(|(dp, _class)| dp.clone()).collect();
+    (train_data, train_classify_data, classify_data)
+}
+
+fn into_chart_dataset(
+    x_axis: usize,
+    y_axis: usize,
+    classes: &[String],
+    rows: &[(Vec<f64>, String)],
+) -> JsValue {
+    let obj = Object::new();
+    set(
+        &obj,
+        "datasets",
+        &{
+            let arr = Array::from_iter(classes.iter().enumerate().map(|(i, class)| {
+                let obj = Object::new();
+                set(&obj, "label", &class.into());
+                set(&obj, "borderColor", &class_color(i, 1.).into());
+                set(&obj, "backgroundColor", &class_color(i, 0.5).into());
+                set(
+                    &obj,
+                    "data",
+                    &Array::from_iter(rows.iter().filter_map(|(vec, label)| {
+                        if label == class {
+                            let obj = Object::new();
+                            set(&obj, "x", &vec[x_axis].into());
+                            set(&obj, "y", &vec[y_axis].into());
+                            Some(obj)
+                        } else {
+                            None
+                        }
+                    })),
+                );
+                obj
+            }));
+            arr
+        }
+        .into(),
+    );
+    obj.into()
+}
+
+// a test point with its true and predicted class and the node number of its leaf
+#[derive(Debug, Clone)]
+struct Prediction {
+    dp: DataPoint,
+    truth: Class,
+    predicted: Class,
+    leaf: usize,
+}
+
+fn collect_predictions(tree: &DecisionTree, data: &[(DataPoint, Class)]) -> Vec<Prediction> {
+    data.iter()
+        .map(|(dp, truth)| Prediction {
+            dp: dp.clone(),
+            truth: *truth,
+            predicted: predict(tree, dp),
+            leaf: leaf_index(tree, dp),
+        })
+        .collect()
+}
+
+// test points grouped by predicted class, misclassified ones drawn as crosses
+fn into_prediction_dataset(
+    x_axis: usize,
+    y_axis: usize,
+    dataset: &DataSet,
+    predictions: &[Prediction],
+    locale: Locale,
+) -> JsValue {
+    let datasets = Array::new();
+    for (i, class) in dataset.classes.iter().enumerate() {
+        let points: Vec<_> = predictions
+            .iter()
+            .filter(|p| p.predicted.0 as usize == i)
+            .collect();
+        let obj = Object::new();
+        set(
+            &obj,
+            "label",
+            &tr!(locale, "预测为 {class}", "predicted {class}").into(),
+        );
+        set(&obj, "borderColor", &class_color(i, 1.).into());
+        set(&obj, "backgroundColor", &class_color(i, 0.5).into());
+        let wrong = |p: &Prediction| p.truth != p.predicted;
+        set(
+            &obj,
+            "pointStyle",
+            &Array::from_iter(
+                points
+                    .iter()
+                    .map(|p| JsValue::from_str(if wrong(p) { "crossRot" } else { "circle" })),
+            ),
+        );
+        set(
+            &obj,
+            "pointRadius",
+            &Array::from_iter(
+                points
+                    .iter()
+                    .map(|p| JsValue::from(if wrong(p) { 7 } else { 3 })),
+            ),
+        );
+        set(
+            &obj,
+            "pointBorderWidth",
+            &Array::from_iter(
+                points
+                    .iter()
+                    .map(|p| JsValue::from(if wrong(p) { 3 } else { 1 })),
+            ),
+        );
+        set(
+            &obj,
+            "data",
+            &Array::from_iter(points.iter().map(|p| {
+                let obj = Object::new();
+                set(&obj, "x", &p.dp.0[x_axis].into());
+                set(&obj, "y", &p.dp.0[y_axis].into());
+                let name = |class: &Class| dataset.class_map.get_by_right(class).unwrap();
+                set(
+                    &obj,
+                    "tooltip",
+                    &tr!(
+                        locale,
+                        "真实: {}, 预测: {}, 叶节点 #{}",
+                        "true: {}, predicted: {}, leaf #{}",
+                        name(&p.truth),
+                        name(&p.predicted),
+                        p.leaf
+                    )
+                    .into(),
+                );
+                obj
+            })),
+        );
+        datasets.push(&obj);
+    }
+    let obj = Object::new();
+    set(&obj, "datasets", &datasets);
+    obj.into()
+}
+
+// rows shaded from white to red by anomaly score, the ones scoring at least
+// `threshold` drawn as crosses in a dataset of their own
+fn into_anomaly_dataset(
+    x_axis: usize,
+    y_axis: usize,
+    dataset: &DataSet,
+    scores: &[f64],
+    threshold: f64,
+    locale: Locale,
+) -> JsValue {
+    let datasets = Array::new();
+    for outliers in [false, true] {
+        let rows: Vec<_> = dataset
+            .rows
+            .iter()
+            .zip(scores)
+            .filter(|(_row, score)| (**score >= threshold) == outliers)
+            .collect();
+        let obj = Object::new();
+        let label = if outliers {
+            tr!(locale, "异常点 ({})", "outliers ({})", rows.len())
+        } else {
+            locale.pick("正常点", "inliers").to_owned()
+        };
+        set(&obj, "label", &label.into());
+        set(&obj, "borderColor", &"rgba(239, 68, 68, 1)".into());
+        set(
+            &obj,
+            "backgroundColor",
+            &Array::from_iter(rows.iter().map(|(_row, score)| {
+                // scores below 0.5 are ordinary, shading starts there
+                let alpha = ((**score - 0.5) * 2.).clamp(0.05, 1.);
+                JsValue::from(format!("rgba(239, 68, 68, {alpha:.3})"))
+            })),
+        );
+        if outliers {
+            set(&obj, "pointStyle", &"crossRot".into());
+            set(&obj, "pointRadius", &7.into());
+            set(&obj, "pointBorderWidth", &3.into());
+        } else {
+            set(&obj, "borderColor", &"rgba(113, 113, 122, 0.5)".into());
+        }
+        set(
+            &obj,
+            "data",
+            &Array::from_iter(rows.iter().map(|((vec, class), score)| {
+                let obj = Object::new();
+                set(&obj, "x", &vec[x_axis].into());
+                set(&obj, "y", &vec[y_axis].into());
+                set(
+                    &obj,
+                    "tooltip",
+                    &tr!(
+                        locale,
+                        "{class}, 异常分数 {score:.3}",
+                        "{class}, anomaly score {score:.3}"
+                    )
+                    .into(),
+                );
+                obj
+            })),
+        );
+        datasets.push(&obj);
+    }
+    let obj = Object::new();
+    set(&obj, "datasets", &datasets);
+    obj.into()
+}
+
+// [(min, max) of x, (min, max) of y] over all rows
+fn data_bounds(x_axis: usize, y_axis: usize, rows: &[(Vec<f64>, String)]) -> [(f64, f64); 2] {
+    let mut bounds = [(f64::MAX, f64::MIN); 2];
+    for (vec, _label) in rows {
+        for (bound, axis) in bounds.iter_mut().zip([x_axis, y_axis]) {
+            bound.0 = bound.0.min(vec[axis]);
+            bound.1 = bound.1.max(vec[axis]);
+        }
+    }
+    bounds
+}
+
+fn region_bounds(
+    x_axis: usize,
+    y_axis: usize,
+    conditions: &[Condition],
+    mut bounds: [(f64, f64); 2],
+) -> [(f64, f64); 2] {
+    for c in conditions {
+        for (bound, axis) in bounds.iter_mut().zip([x_axis, y_axis]) {
+            if c.axis.0 as usize == axis {
+                bound.0 = c.lower.map_or(bound.0, |lower| bound.0.max(lower));
+                bound.1 = c.upper.map_or(bound.1, |upper| bound.1.min(upper));
+            }
+        }
+    }
+    bounds
+}
+
+fn rectangle([(x_min, x_max), (y_min, y_max)]: [(f64, f64); 2]) -> Vec<(f64, f64)> {
+    vec![
+        (x_min, y_min),
+        (x_max, y_min),
+        (x_max, y_max),
+        (x_min, y_max),
+    ]
+}
+
+// (a, b, c) for the line a·x + b·y = c on the plotted axes
+type Line = (f64, f64, f64);
+
+fn criterion_line(x_axis: usize, y_axis: usize, c: &Criterion) -> Option<Line> {
+    if c.axis.0 as usize == x_axis {
+        Some((1., 0., c.split))
+    } else if c.axis.0 as usize == y_axis {
+        Some((0., 1., c.split))
+    } else {
+        None
+    }
+}
+
+// an oblique split, sliced at the mean of the axes that are not plotted
+fn hyperplane_line(x_axis: usize, y_axis: usize, h: &Hyperplane, means: &[f64]) -> Option<Line> {
+    let (mut a, mut b, mut c) = (0., 0., h.split);
+    for (axis, w) in &h.weights {
+        match axis.0 as usize {
+            axis if axis == x_axis => a += w,
+            axis if axis == y_axis => b += w,
+            axis => c -= w * means[axis],
+        }
+    }
+    (a != 0. || b != 0.).then_some((a, b, c))
+}
+
+fn side((a, b, c): Line, (x, y): (f64, f64)) -> f64 {
+    a * x + b * y - c
+}
+
+fn intersection(line: Line, p: (f64, f64), q: (f64, f64)) -> (f64, f64) {
+    let (sp, sq) = (side(line, p), side(line, q));
+    let t = sp / (sp - sq);
+    (p.0 + t * (q.0 - p.0), p.1 + t * (q.1 - p.1))
+}
+
+// the part of a convex polygon below (a·x + b·y < c) or above the line
+fn clip(polygon: &[(f64, f64)], line: Line, below: bool) -> Vec<(f64, f64)> {
+    let inside = |point| (side(line, point) < 0.) == below;
+    let mut clipped = Vec::new();
+    for (i, p) in polygon.iter().enumerate() {
+        let q = polygon[(i + 1) % polygon.len()];
+        if inside(*p) {
+            clipped.push(*p);
+        }
+        if inside(*p) != inside(q) {
+            clipped.push(intersection(line, *p, q));
+        }
+    }
+    clipped
+}
+
+// the segment of the line inside a convex polygon
+fn chord(polygon: &[(f64, f64)], line: Line) -> Option<[(f64, f64); 2]> {
+    let points: Vec<(f64, f64)> = polygon
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (*p, polygon[(i + 1) % polygon.len()]))
+        .filter(|(p, q)| (side(line, *p) < 0.) != (side(line, *q) < 0.))
+        .map(|(p, q)| intersection(line, p, q))
+        .collect();
+    (points.len() >= 2).then(|| [points[0], points[points.len() - 1]])
+}
+
+// regions are convex polygons on the plotted axes, splits on other axes leave
+// them as they are
+fn partition_lines(
+    x_axis: usize,
+    y_axis: usize,
+    tree: &DecisionTree,
+    polygon: &[(f64, f64)],
+    means: &[f64],
+    lines: &mut Vec<[(f64, f64); 2]>,
+) {
+    let (line, left_tree, right_tree) = match tree {
+        DecisionTree::Branch(c, left_tree, right_tree) => {
+            (criterion_line(x_axis, y_axis, c), left_tree, right_tree)
+        }
+        DecisionTree::Oblique(h, left_tree, right_tree) => (
+            hyperplane_line(x_axis, y_axis, h, means),
+            left_tree,
+            right_tree,
+        ),
+        DecisionTree::Multiway(multiway, subtrees) => {
+            // children lie between consecutive thresholds, cut off one at a time
+            let mut rest = polygon.to_vec();
+            for (threshold, subtree) in multiway.thresholds.iter().zip(subtrees) {
+                let c = Criterion {
+                    axis: multiway.axis,
+                    split: *threshold,
+                    p_value: None,
+                };
+                let child = match criterion_line(x_axis, y_axis, &c) {
+                    Some(line) => {
+                        lines.extend(chord(&rest, line));
+                        let child = clip(&rest, line, true);
+                        rest = clip(&rest, line, false);
+                        child
+                    }
+                    None => rest.clone(),
+                };
+                partition_lines(x_axis, y_axis, subtree, &child, means, lines);
+            }
+            let last = subtrees.last().unwrap();
+            partition_lines(x_axis, y_axis, last, &rest, means, lines);
+            return;
+        }
+        DecisionTree::Leave(_) => return,
+    };
+    let (left, right) = match line {
+        Some(line) => {
+            lines.extend(chord(polygon, line));
+            (clip(polygon, line, true), clip(polygon, line, false))
+        }
+        None => (polygon.to_vec(), polygon.to_vec()),
+    };
+    partition_lines(x_axis, y_axis, left_tree, &left, means, lines);
+    partition_lines(x_axis, y_axis, right_tree, &right, means, lines);
+}
+
+// all lines in one dataset, separated by null points so they are not joined
+fn into_line_dataset(
+    label: &str,
+    color: &str,
+    width: f64,
+    lines: &[impl AsRef<[(f64, f64)]>],
+) -> JsValue {
+    let obj = Object::new();
+    set(&obj, "label", &label.into());
+    set(&obj, "showLine", &true.into());
+    set(&obj, "borderColor", &color.into());
+    set(&obj, "backgroundColor", &color.into());
+    set(&obj, "borderWidth", &width.into());
+    set(&obj, "pointRadius", &0.into());
+    set(
+        &obj,
+        "data",
+        &Array::from_iter(lines.iter().flat_map(|line| {
+            line.as_ref().iter().map(Some).chain([None]).map(|point| {
+                let obj = Object::new();
+                let (x, y) = point.map_or((JsValue::NULL, JsValue::NULL), |(x, y)| {
+                    ((*x).into(), (*y).into())
+                });
+                set(&obj, "x", &x);
+                set(&obj, "y", &y);
+                obj
+            })
+        })),
+    );
+    obj.into()
+}
+
+fn into_point_dataset(label: &str, color: &str, (x, y): (f64, f64)) -> JsValue {
+    let obj = Object::new();
+    set(&obj, "label", &label.into());
+    set(&obj, "borderColor", &color.into());
+    set(&obj, "backgroundColor", &color.into());
+    set(&obj, "pointRadius", &6.into());
+    let point = Object::new();
+    set(&point, "x", &x.into());
+    set(&point, "y", &y.into());
+    set(&obj, "data", &Array::of1(&point));
+    obj.into()
+}
+
+// the first lowest point, the one the search picks
+fn lowest(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    points.iter().copied().min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+fn display_impurity(impurity: Impurity, locale: Locale) -> &'static str {
+    match impurity {
+        Impurity::Gini => "Gini",
+        Impurity::Entropy => locale.pick("熵", "entropy"),
+    }
+}
+
+fn display_hyperparameters(hyperparameters: &Hyperparameters, locale: Locale) -> String {
+    tr!(
+        locale,
+        "最大深度 {}, 叶节点最少 {} 个点, {}, 剪枝 α = {}",
+        "max depth {}, at least {} points per leaf, {}, pruning α = {}",
+        hyperparameters.max_depth,
+        hyperparameters.min_elements_per_leaf,
+        display_impurity(hyperparameters.impurity, locale),
+        hyperparameters.ccp_alpha
+    )
+}
+
+fn display_tree(dataset: &DataSet, tree: &DecisionTree, indent: usize) -> String {
+    let indent_s = repeat_n(' ', indent * 2).collect::<String>();
+    match tree {
+        DecisionTree::Branch(c, left_tree, right_tree) => {
+            let left = display_tree(dataset, left_tree, indent + 1);
+            let right = display_tree(dataset, right_tree, indent + 1);
+            // the p-value of the test that chose the axis, for conditional inference
+            let p_value = c
+                .p_value
+                .map(|p| format!(" (p = {p:.2e})"))
+                .unwrap_or_default();
+            format!(
+                "{}if {} < {:.3} {{{}\n{}\n{}}} else {{\n{}\n{}}}",
+                indent_s,
+                dataset.axis_map.get_by_right(&c.axis).unwrap(),
+                c.split,
+                p_value,
+                left,
+                indent_s,
+                right,
+                indent_s
+            )
+        }
+        DecisionTree::Oblique(hyperplane, left_tree, right_tree) => {
+            let left = display_tree(dataset, left_tree, indent + 1);
+            let right = display_tree(dataset, right_tree, indent + 1);
+            format!(
+                "{}if {} {{\n{}\n{}}} else {{\n{}\n{}}}",
+                indent_s,
+                display_hyperplane(dataset, hyperplane),
+                left,
+                indent_s,
+                right,
+                indent_s
+            )
+        }
+        DecisionTree::Multiway(multiway, subtrees) => {
+            let name = dataset.axis_map.get_by_right(&multiway.axis).unwrap();
+            let mut text = String::new();
+            for (i, subtree) in subtrees.iter().enumerate() {
+                let test = match (i, multiway.thresholds.get(i)) {
+                    (0, Some(t)) => format!("if {name} < {t:.3} {{"),
+                    (_, Some(t)) => format!("}} else if {name} < {t:.3} {{"),
+                    (_, None) => "} else {".to_owned(),
+                };
+                let subtree = display_tree(dataset, subtree, indent + 1);
+                text += &format!("{indent_s}{test}\n{subtree}\n");
+            }
+            text + &indent_s + "}"
+        }
+        DecisionTree::Leave(class) => {
+            format!(
+                "{}{}",
+                indent_s,
+                dataset.class_map.get_by_right(class).unwrap()
+            )
+        }
+    }
+}
+
+fn assess(dataset: &DataSet, result: &HashMap<Class, Vec<DataPoint>>) -> f64 {
+    let mut correct: usize = 0;
+    let mut all: usize = 0;
+    for (class, data) in result {
+        for dp in data {
+            if dataset.rows.iter().find(|row| row.0 == dp.0).unwrap().1
+                == *dataset.class_map.get_by_right(class).unwrap()
+            {
+                correct += 1;
+            }
+            all += 1;
+        }
+    }
+    correct as f64 / all as f64
+}
+
+fn log(log_ref: NodeRef<html::Custom>, line: &str) {
+    let element = log_ref.get_untracked().unwrap().into_any().clone();
+    let content = get_property(&element, "value")
+        .unwrap_or_default()
+        .as_string()
+        .unwrap();
+    set_property(&element, "value", &Some((content + line + "\n").into()));
+}
+
+// `log!(log_ref, locale, "中文 {x}", "English {x}", ...)`
+macro_rules! log {
+    ($e:expr, $locale:expr, $($t:tt)*) => (log($e, &tr!($locale, $($t)*)))
+}
+
+fn display_split_search(split_search: SplitSearch, locale: Locale) -> String {
+    match split_search {
+        SplitSearch::Exact => locale.pick("精确搜索", "exact search").to_owned(),
+        SplitSearch::Histogram { max_bins } => {
+            tr!(
+                locale,
+                "直方图 ({max_bins} 箱)",
+                "histogram ({max_bins} bins)"
+            )
+        }
+        SplitSearch::Oblique => locale.pick("斜分裂", "oblique splits").to_owned(),
+    }
+}
+
+// the split search only applies to CART
+fn display_method(hyperparameters: &Hyperparameters, locale: Locale) -> String {
+    match hyperparameters.algorithm {
+        Algorithm::Cart => format!(
+            "CART, {}",
+            display_split_search(hyperparameters.split_search, locale)
+        ),
+        Algorithm::Id3 => "ID3".to_owned(),
+        Algorithm::C45 => "C4.5".to_owned(),
+        Algorithm::Conditional => format!("ctree, α = {}", hyperparameters.significance),
+    }
+}
+
+fn timestamp() -> f64 {
+    window().performance().unwrap().now() as f64 / 1000.
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RuleOrder {
+    Length,
+    Class,
+    Coverage,
+    Support,
+    Confidence,
+}
+
+#[component]
+fn RuleTable(
+    dataset: ReadSignal<Option<DataSet>>,
+    rules: ReadSignal<Option<Vec<Rule>>>,
+) -> impl IntoView {
+    let locale = expect_context::<ReadSignal<Locale>>();
+    let (order, set_order) = create_signal((RuleOrder::Coverage, true));
+    let sort_by = move |key: RuleOrder| {
+        let (previous, descending) = order.get_untracked();
+        set_order((key, if previous == key { !descending } else { true }));
+    };
+    let sorted = move || {
+        let mut rules = rules().unwrap_or_default();
+        let (key, descending) = order();
+        rules.sort_by(|a, b| {
+            let ordering = match key {
+                RuleOrder::Length => (a.conditions.len() + a.half_spaces.len())
+                    .cmp(&(b.conditions.len() + b.half_spaces.len())),
+                RuleOrder::Class => a.class.0.cmp(&b.class.0),
+                RuleOrder::Coverage => a.coverage.cmp(&b.coverage),
+                RuleOrder::Support => a.support.total_cmp(&b.support),
+                RuleOrder::Confidence => a.confidence.total_cmp(&b.confidence),
+            };
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+        rules
+    };
+    let (class_name, style_val) = style_str! {
+        table {
+            width: 100%;
+            border-collapse: collapse;
+            font-size: 0.875rem;
+        }
+        th {
+            cursor: pointer;
+            user-select: none;
+        }
+        th, td {
+            border-bottom: 1px solid #e4e4e7;
+            padding: 0.25rem 0.5rem;
+            text-align: left;
+        }
+    };
+    view! {
+        class = class_name,
+        <Style> { style_val } </Style>
+        <Show when=move || rules().is_some_and(|rules| !rules.is_empty())>
+            <table>
+                <thead>
+                    <tr>
+                        <th on:click=move |_| sort_by(RuleOrder::Length)> {move || locale().pick("规则", "Rule")} </th>
+                        <th on:click=move |_| sort_by(RuleOrder::Class)> {move || locale().pick("分类", "Class")} </th>
+                        <th on:click=move |_| sort_by(RuleOrder::Coverage)> {move || locale().pick("覆盖数", "Coverage")} </th>
+                        <th on:click=move |_| sort_by(RuleOrder::Support)> {move || locale().pick("支持度", "Support")} </th>
+                        <th on:click=move |_| sort_by(RuleOrder::Confidence)> {move || locale().pick("置信度", "Confidence")} </th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {move || {
+                        let dataset = dataset().unwrap();
+                        let locale = locale();
+                        sorted()
+                            .into_iter()
+                            .map(|rule| {
+                                view! {
+                                    class = class_name,
+                                    <tr>
+                                        <td> {display_conditions(&dataset, &rule.conditions, &rule.half_spaces, locale)} </td>
+                                        <td> {dataset.class_map.get_by_right(&rule.class).unwrap().clone()} </td>
+                                        <td> {rule.coverage} </td>
+                                        <td> {format!("{:.3}%", rule.support * 100.)} </td>
+                                        <td> {format!("{:.3}%", rule.confidence * 100.)} </td>
+                                    </tr>
+                                }
+                            })
+                            .collect_view()
+                    }}
+                </tbody>
+            </table>
+        </Show>
+    }
+}
+
+#[component]
+fn StepPanel(
+    dataset: ReadSignal<Option<DataSet>>,
+    // the hyperparameters the steps are grown with
+    hyperparameters: ReadSignal<Hyperparameters>,
+    step: ReadSignal<Option<Step>>,
+) -> impl IntoView {
+    let locale = expect_context::<ReadSignal<Locale>>();
+    let (class_name, style_val) = style_str! {
+        table {
+            width: 100%;
+            border-collapse: collapse;
+            font-size: 0.875rem;
+        }
+        th, td {
+            border-bottom: 1px solid #e4e4e7;
+            padding: 0.25rem 0.5rem;
+            text-align: left;
+        }
+        .chosen {
+            font-weight: bold;
+        }
+        p {
+            margin: 0.25rem 0;
+        }
+    };
+    view! {
+        class = class_name,
+        <Style> { style_val } </Style>
+        {move || {
+            let dataset = dataset()?;
+            let step = step()?;
+            let locale = locale();
+            let impurity_name = display_impurity(hyperparameters().impurity, locale);
+            let axis_name = |axis: &Axis| dataset.axis_map.get_by_right(axis).unwrap().clone();
+            let class_name_of = |class: &Class| dataset.class_map.get_by_right(class).unwrap().clone();
+            let counts = dataset
+                .mapped_class
+                .iter()
+                .zip(step.counts.iter())
+                .map(|(class, count)| format!("{} {count}", class_name_of(class)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let outcome = match step.criterion {
+                Some(c) => tr!(
+                    locale,
+                    "按 {} < {:.3} 分裂",
+                    "split on {} < {:.3}",
+                    axis_name(&c.axis),
+                    c.split
+                ),
+                None => tr!(
+                    locale,
+                    "成为叶子, 分类为 {}",
+                    "becomes a leaf of class {}",
+                    class_name_of(&step.class)
+                ),
+            };
+            let rows = step
+                .curves
+                .iter()
+                .map(|curve| {
+                    let best = lowest(&curve.points);
+                    let chosen = step.criterion.is_some_and(|c| c.axis == curve.axis);
+                    view! {
+                        class = class_name,
+                        <tr class:chosen=chosen>
+                            <td> {axis_name(&curve.axis)} </td>
+                            <td> {curve.points.len()} </td>
+                            <td> {best.map_or("-".to_owned(), |(split, _)| format!("{split:.3}"))} </td>
+                            <td> {best.map_or("-".to_owned(), |(_, impurity)| format!("{impurity:.4}"))} </td>
+                        </tr>
+                    }
+                })
+                .collect_view();
+            Some(view! {
+                class = class_name,
+                <p> {tr!(locale, "节点 #{}, 深度 {}, 区域: {}", "node #{}, depth {}, region: {}", step.node, step.depth, display_conditions(&dataset, &step.conditions, &[], locale))} </p>
+                <p> {tr!(locale, "各类点数: {counts}, {impurity_name} {:.4}, {outcome}", "points per class: {counts}, {impurity_name} {:.4}, {outcome}", step.impurity)} </p>
+                <Show when=move || !step.curves.is_empty()>
+                    <table>
+                        <thead>
+                            <tr>
+                                <th> {locale.pick("特征", "Feature")} </th>
+                                <th> {locale.pick("候选阈值数", "Thresholds tried")} </th>
+                                <th> {locale.pick("最佳阈值", "Best threshold")} </th>
+                                <th> {tr!(locale, "加权{impurity_name}", "Weighted {impurity_name}")} </th>
+                            </tr>
+                        </thead>
+                        <tbody> {rows.clone()} </tbody>
+                    </table>
+                </Show>
+            })
+        }}
+    }
+}
+
+// a scatter chart with linear, titled axes, for plotting lines of points
+fn line_chart_config(x_title: &str, y_title: &str) -> Object {
+    let title = |text: &str| {
+        let obj = Object::new();
+        set(&obj, "display", &true.into());
+        set(&obj, "text", &text.into());
+        obj
+    };
+    let x = Object::new();
+    set(&x, "type", &"linear".into());
+    set(&x, "title", &title(x_title));
+    let y = Object::new();
+    set(&y, "title", &title(y_title));
+    let scales = Object::new();
+    set(&scales, "x", &x);
+    set(&scales, "y", &y);
+    let options = Object::new();
+    set(&options, "scales", &scales);
+    let config = Object::new();
+    set(&config, "type", &"scatter".into());
+    set(&config, "options", &options);
+    config
+}
+
+// changes the axis titles of a chart made by `line_chart_config`
+fn set_chart_titles(chart: &JsValue, x_title: &str, y_title: &str) {
+    let scales = get(&get(chart, "options"), "scales");
+    set(&get(&get(&scales, "x"), "title"), "text", &x_title.into());
+    set(&get(&get(&scales, "y"), "title"), "text", &y_title.into());
+}
+
+// weighted impurity of every threshold tried at a node of the tree, against the threshold
+#[component]
+fn ImpurityChart(
+    dataset: ReadSignal<Option<DataSet>>,
+    train_data: ReadSignal<Option<LabelledData>>,
+    tree: ReadSignal<Option<DecisionTree>>,
+    hyperparameters: ReadSignal<Hyperparameters>,
+    step: ReadSignal<Option<Step>>,
+) -> impl IntoView {
+    let locale = expect_context::<ReadSignal<Locale>>();
+    let (node, set_node) = create_signal(None::<usize>);
+    let (axis, set_axis) = create_signal(None::<Axis>);
+    create_effect(move |_| {
+        dataset.track();
+        set_node(None);
+        set_axis(None);
+    });
+    // follow the node being expanded while stepping
+    create_effect(move |_| {
+        if let Some(step) = step() {
+            set_node(Some(step.node));
+            set_axis(None);
+        }
+    });
+    let tree_regions = move || tree().map_or_else(Vec::new, |tree| regions(&tree));
+    let region = move || tree_regions().get(node()?).cloned();
+    // the axis the node is split on unless another one is picked
+    let shown_axis = move || {
+        let default = region()
+            .and_then(|region| region.criterion)
+            .map(|c| c.axis)
+            .or_else(|| dataset().map(|dataset| dataset.mapped_axis[0]));
+        axis().or(default)
+    };
+    let chart_ref: NodeRef<html::Canvas> = create_node_ref();
+    create_effect(move |chart: Option<Option<JsValue>>| {
+        let canvas = chart_ref()?;
+        let locale = locale();
+        let (x_title, y_title) =
+            locale.pick(("阈值", "加权不纯度"), ("threshold", "weighted impurity"));
+        let chart = chart.flatten().unwrap_or_else(|| {
+            new_chart(
+                &(*canvas.into_any()).clone(),
+                &line_chart_config(x_title, y_title),
+            )
+        });
+        set_chart_titles(&chart, x_title, y_title);
+        let datasets = Array::new();
+        if let (Some(dataset), Some(train_data), Some(region), Some(axis)) =
+            (dataset(), train_data(), region(), shown_axis())
+        {
+            let data: LabelledData = train_data
+                .into_iter()
+                .filter(|(dp, _class)| region.matches(dp))
+                .collect();
+            let hyperparameters = hyperparameters();
+            let curve = impurity_curves(
+                &dataset.mapped_axis,
+                &dataset.mapped_class,
+                &data,
+                &hyperparameters,
+            )
+            .into_iter()
+            .find(|curve| curve.axis == axis)
+            .unwrap();
+            datasets.push(&into_line_dataset(
+                dataset.axis_map.get_by_right(&axis).unwrap(),
+                "#3b82f6",
+                2.,
+                &[&curve.points],
+            ));
+            let chosen = region.criterion.filter(|c| c.axis == axis).and_then(|c| {
+                curve
+                    .points
+                    .iter()
+                    .copied()
+                    .find(|(split, _)| *split == c.split)
+            });
+            if let Some(point) = chosen {
+                datasets.push(&into_point_dataset(
+                    locale.pick("选中的分裂", "chosen split"),
+                    "#ef4444",
+                    point,
+                ));
+            } else if let Some(point) = lowest(&curve.points) {
+                datasets.push(&into_point_dataset(
+                    locale.pick("该特征最佳阈值", "best threshold on this feature"),
+                    "#f59e0b",
+                    point,
+                ));
+            }
+        }
+        let data = Object::new();
+        set(&data, "datasets", &datasets);
+        set(&chart, "data", &data);
+        update_chart(&chart);
+        Some(chart)
+    });
+    let (class_name, style_val) = style_str! {
+        .controls {
+            display: flex;
+            flex-direction: row;
+            gap: 1rem;
+        }
+        .controls sl-select {
+            flex: 1;
+        }
+    };
+    view! {
+        class = class_name,
+        <Style> { style_val } </Style>
+        <div class="controls">
+            <sl-select label=move || locale().pick("不纯度曲线: 节点", "Impurity curve: node") prop:value=move || node().map_or_else(String::new, |node| node.to_string())
+                on:sl-change=move |ev: JsValue| {
+                    set_node(get(&get(&ev, "target"), "value").as_string().unwrap().parse().ok());
+                    set_axis(None);
+                }>
+                {move || {
+                    let dataset = dataset()?;
+                    let locale = locale();
+                    Some(
+                        tree_regions()
+                            .into_iter()
+                            .enumerate()
+                            .map(|(i, region)| {
+                                view! {
+                                    <sl-option value=i.to_string()>
+                                        {format!("#{i} {}", display_conditions(&dataset, &region.conditions, &region.half_spaces, locale))}
+                                    </sl-option>
+                                }
+                            })
+                            .collect_view(),
+                    )
+                }}
+            </sl-select>
+            <sl-select label=move || locale().pick("特征", "Feature") prop:value=move || shown_axis().map_or_else(String::new, |axis| axis.0.to_string())
+                on:sl-change=move |ev: JsValue| {
+                    set_axis(get(&get(&ev, "target"), "value").as_string().unwrap().parse().ok().map(Axis));
+                }>
+                {move || {
+                    let dataset = dataset()?;
+                    Some(
+                        dataset
+                            .mapped_axis
+                            .iter()
+                            .map(|axis| {
+                                view! {
+                                    <sl-option value=axis.0.to_string()>
+                                        {dataset.axis_map.get_by_right(axis).unwrap().clone()}
+                                    </sl-option>
+                                }
+                            })
+                            .collect_view(),
+                    )
+                }}
+            </sl-select>
+        </div>
+        <div class="chart">
+            <canvas ref=chart_ref />
+        </div>
+    }
+}
+
+#[component]
+fn SearchTable(results: ReadSignal<Option<Vec<SearchResult>>>) -> impl IntoView {
+    let locale = expect_context::<ReadSignal<Locale>>();
+    let (class_name, style_val) = style_str! {
+        div {
+            max-height: 20rem;
+            overflow-y: auto;
+        }
+        table {
+            width: 100%;
+            border-collapse: collapse;
+            font-size: 0.875rem;
+        }
+        th, td {
+            border-bottom: 1px solid #e4e4e7;
+            padding: 0.25rem 0.5rem;
+            text-align: left;
+        }
+    };
+    view! {
+        class = class_name,
+        <Style> { style_val } </Style>
+        <Show when=move || results().is_some_and(|results| !results.is_empty())>
+            <div>
+                <table>
+                    <thead>
+                        <tr>
+                            <th> {move || locale().pick("排名", "Rank")} </th>
+                            <th> {move || locale().pick("最大深度", "Max depth")} </th>
+                            <th> {move || locale().pick("叶节点最少点数", "Min points per leaf")} </th>
+                            <th> {move || locale().pick("不纯度", "Impurity")} </th>
+                            <th> {move || locale().pick("剪枝 α", "Pruning α")} </th>
+                            <th> {move || locale().pick("平均正确率", "Mean accuracy")} </th>
+                            <th> {move || locale().pick("标准差", "Std")} </th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || {
+                            let locale = locale();
+                            results()
+                                .unwrap_or_default()
+                                .into_iter()
+                                .enumerate()
+                                .map(|(rank, result)| {
+                                    let hyperparameters = result.hyperparameters;
+                                    view! {
+                                        class = class_name,
+                                        <tr>
+                                            <td> {rank + 1} </td>
+                                            <td> {hyperparameters.max_depth} </td>
+                                            <td> {hyperparameters.min_elements_per_leaf} </td>
+                                            <td> {display_impurity(hyperparameters.impurity, locale)} </td>
+                                            <td> {hyperparameters.ccp_alpha} </td>
+                                            <td> {format!("{:.3}%", result.mean * 100.)} </td>
+                                            <td> {format!("{:.3}%", result.std * 100.)} </td>
+                                        </tr>
+                                    }
+                                })
+                                .collect_view()
+                        }}
+                    </tbody>
+                </table>
+            </div>
+        </Show>
+    }
+}
+
+// a trained tree kept in the registry of the current dataset
+#[derive(Debug, Clone)]
+struct RegisteredModel {
+    id: usize,
+    tree: DecisionTree,
+    hyperparameters: Hyperparameters,
+    train_accuracy: f64,
+    test_accuracy: f64,
+    leaves: usize,
+    depth: usize,
+    // seconds
+    duration: f64,
+}
+
+// the trees trained on the current dataset side by side, any two of them can be
+// compared with McNemar's test on the test split
+#[component]
+fn ModelRegistry(
+    models: ReadSignal<Vec<RegisteredModel>>,
+    set_models: WriteSignal<Vec<RegisteredModel>>,
+    test_data: ReadSignal<Option<LabelledData>>,
+    log_ref: NodeRef<html::Custom>,
+    #[prop(into)] on_show: Callback<usize>,
+) -> impl IntoView {
+    let locale = expect_context::<ReadSignal<Locale>>();
+    let (first, set_first) = create_signal(None::<usize>);
+    let (second, set_second) = create_signal(None::<usize>);
+    let (test, set_test) = create_signal(None::<(usize, usize, McNemar)>);
+    // the selection follows the two latest models until one is picked
+    create_effect(move |_| {
+        let ids: Vec<usize> = models().iter().map(|model| model.id).collect();
+        let keep = |id: Option<usize>| id.filter(|id| ids.contains(id));
+        let (latest, previous) = (ids.last().copied(), ids.iter().rev().nth(1).copied());
+        set_first(keep(first.get_untracked()).or(previous));
+        set_second(keep(second.get_untracked()).or(latest));
+        if test
+            .get_untracked()
+            .is_some_and(|(a, b, _test)| !ids.contains(&a) || !ids.contains(&b))
+        {
+            set_test(None);
+        }
+    });
+    let compare = move |_| {
+        let (Some(a), Some(b)) = (first.get_untracked(), second.get_untracked()) else {
+            return;
+        };
+        let Some(test_data) = test_data.get_untracked() else {
+            return;
+        };
+        let models = models.get_untracked();
+        let tree = |id: usize| &models.iter().find(|model| model.id == id).unwrap().tree;
+        let result = mcnemar(tree(a), tree(b), &test_data);
+        log!(
+            log_ref,
+            locale.get_untracked(),
+            "McNemar 检验 #{a} 对 #{b}: 仅 #{a} 正确 {} 个, 仅 #{b} 正确 {} 个, χ² = {:.3}, p = {:.4}",
+            "McNemar test #{a} vs #{b}: {} only #{a} gets right, {} only #{b} gets right, χ² = {:.3}, p = {:.4}",
+            result.only_first,
+            result.only_second,
+            result.statistic,
+            result.p_value
+        );
+        set_test(Some((a, b, result)));
+    };
+    let remove = move |id: usize| set_models.update(|models| models.retain(|model| model.id != id));
+    let model_options = move || {
+        let locale = locale();
+        models()
+            .into_iter()
+            .map(|model| {
+                view! {
+                    <sl-option value=model.id.to_string()>
+                        {format!("#{} {}", model.id, display_method(&model.hyperparameters, locale))}
+                    </sl-option>
+                }
+            })
+            .collect_view()
+    };
+    let parse = |ev: JsValue| {
+        get(&get(&ev, "target"), "value")
+            .as_string()
+            .and_then(|value| value.parse().ok())
+    };
+    let (class_name, style_val) = style_str! {
+        .models {
+            max-height: 20rem;
+            overflow-y: auto;
+        }
+        table {
+            width: 100%;
+            border-collapse: collapse;
+            font-size: 0.875rem;
+        }
+        th, td {
+            border-bottom: 1px solid #e4e4e7;
+            padding: 0.25rem 0.5rem;
+            text-align: left;
+        }
+        .best {
+            font-weight: bold;
+        }
+        .compare {
+            display: flex;
+            flex-direction: row;
+            align-items: end;
+            gap: 1rem;
+            margin-top: 0.5rem;
+        }
+        .verdict {
+            margin: 0.5rem 0;
+        }
+    };
+    view! {
+        class = class_name,
+        <Style> { style_val } </Style>
+        <Show when=move || !models().is_empty()>
+            <div class="models">
+                <table>
+                    <thead>
+                        <tr>
+                            <th> "#" </th>
+                            <th> {move || locale().pick("算法", "Algorithm")} </th>
+                            <th> {move || locale().pick("超参数", "Hyperparameters")} </th>
+                            <th> {move || locale().pick("训练集正确率", "Train accuracy")} </th>
+                            <th> {move || locale().pick("测试集正确率", "Test accuracy")} </th>
+                            <th> {move || locale().pick("叶节点", "Leaves")} </th>
+                            <th> {move || locale().pick("深度", "Depth")} </th>
+                            <th> {move || locale().pick("用时", "Time")} </th>
+                            <th />
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || {
+                            let locale = locale();
+                            let models = models();
+                            let best = models
+                                .iter()
+                                .map(|model| model.test_accuracy)
+                                .fold(f64::MIN, f64::max);
+                            models
+                                .into_iter()
+                                .map(|model| {
+                                    let id = model.id;
+                                    view! {
+                                        class = class_name,
+                                        <tr>
+                                            <td> {id} </td>
+                                            <td> {display_method(&model.hyperparameters, locale)} </td>
+                                            <td> {display_hyperparameters(&model.hyperparameters, locale)} </td>
+                                            <td> {format!("{:.3}%", model.train_accuracy * 100.)} </td>
+                                            <td class:best=model.test_accuracy == best> {format!("{:.3}%", model.test_accuracy * 100.)} </td>
+                                            <td> {model.leaves} </td>
+                                            <td> {model.depth} </td>
+                                            <td> {format!("{:.3} s", model.duration)} </td>
+                                            <td>
+                                                <sl-button size="small" on:click=move |_| on_show(id)> {locale.pick("显示", "Show")} </sl-button>
+                                                <sl-button size="small" on:click=move |_| remove(id)> {locale.pick("移除", "Remove")} </sl-button>
+                                            </td>
+                                        </tr>
+                                    }
+                                })
+                                .collect_view()
+                        }}
+                    </tbody>
+                </table>
+            </div>
+            <div class="compare">
+                <sl-select label=move || locale().pick("模型 A", "Model A") prop:value=move || first().map(|id| id.to_string()).unwrap_or_default() on:sl-change=move |ev: JsValue| set_first(parse(ev))>
+                    {model_options}
+                </sl-select>
+                <sl-select label=move || locale().pick("模型 B", "Model B") prop:value=move || second().map(|id| id.to_string()).unwrap_or_default() on:sl-change=move |ev: JsValue| set_second(parse(ev))>
+                    {model_options}
+                </sl-select>
+                <sl-button disabled=move || first().is_none() || second().is_none() || first() == second() on:click=compare>
+                    {move || locale().pick("McNemar 检验", "McNemar test")}
+                </sl-button>
+            </div>
+            {move || {
+                let (a, b, test) = test()?;
+                let locale = locale();
+                let verdict = if test.p_value < MCNEMAR_SIGNIFICANCE {
+                    tr!(
+                        locale,
+                        "p = {:.4} < {MCNEMAR_SIGNIFICANCE}: #{a} 与 #{b} 的测试集正确率有显著差异",
+                        "p = {:.4} < {MCNEMAR_SIGNIFICANCE}: the test accuracies of #{a} and #{b} differ significantly",
+                        test.p_value
+                    )
+                } else {
+                    tr!(
+                        locale,
+                        "p = {:.4}: 没有证据表明 #{a} 与 #{b} 的测试集正确率不同",
+                        "p = {:.4}: no evidence that the test accuracies of #{a} and #{b} differ",
+                        test.p_value
+                    )
+                };
+                Some(view! {
+                    class = class_name,
+                    <table>
+                        <thead>
+                            <tr>
+                                <th />
+                                <th> {tr!(locale, "#{b} 正确", "#{b} right")} </th>
+                                <th> {tr!(locale, "#{b} 错误", "#{b} wrong")} </th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            <tr>
+                                <th> {tr!(locale, "#{a} 正确", "#{a} right")} </th>
+                                <td> {test.both_correct} </td>
+                                <td> {test.only_first} </td>
+                            </tr>
+                            <tr>
+                                <th> {tr!(locale, "#{a} 错误", "#{a} wrong")} </th>
+                                <td> {test.only_second} </td>
+                                <td> {test.both_wrong} </td>
+                            </tr>
+                        </tbody>
+                    </table>
+                    <p class="verdict"> {verdict} </p>
+                })
+            }}
+        </Show>
+    }
+}
+
+fn class_color(class: usize, alpha: f64) -> String {
+    format!(
+        "rgba({}, {alpha})",
+        CLASS_COLORS[class % CLASS_COLORS.len()]
+    )
+}
+
+// the class each row is coloured by, as its position in `dataset.classes`:
+// the predicted one if a tree is given, the true one otherwise
+fn row_classes(dataset: &DataSet, tree: Option<&DecisionTree>) -> Vec<usize> {
+    dataset
+        .rows
+        .iter()
+        .map(|(vec, label)| match tree {
+            Some(tree) => predict(tree, &DataPoint(vec.clone())).0 as usize,
+            None => dataset.classes.iter().position(|c| c == label).unwrap(),
+        })
+        .collect()
+}
+
+// (min, max) of every axis over all rows
+fn axis_bounds(dataset: &DataSet) -> Vec<(f64, f64)> {
+    (0..dataset.axises.len())
+        .map(|axis| {
+            dataset
+                .rows
+                .iter()
+                .fold((f64::MAX, f64::MIN), |(min, max), (vec, _label)| {
+                    (min.min(vec[axis]), max.max(vec[axis]))
+                })
+        })
+        .collect()
+}
+
+fn normalize(value: f64, (min, max): (f64, f64)) -> f64 {
+    if max > min {
+        (value - min) / (max - min)
+    } else {
+        0.5
+    }
+}
+
+// every pair of axes as a thumbnail on one canvas, rows are the Y axis and
+// columns the X axis; a lines chart with one line per row across all axes
+#[component]
+fn Overview(
+    dataset: ReadSignal<Option<DataSet>>,
+    tree: ReadSignal<Option<DecisionTree>>,
+    x_axis: ReadSignal<Option<usize>>,
+    y_axis: ReadSignal<Option<usize>>,
+    #[prop(into)] on_select: Callback<(usize, usize)>,
+) -> impl IntoView {
+    let locale = expect_context::<ReadSignal<Locale>>();
+    let (by_prediction, set_by_prediction) = create_signal(false);
+    let colouring = move || tree().filter(|_| by_prediction());
+    let matrix_ref: NodeRef<html::Canvas> = create_node_ref();
+    create_effect(move |_| {
+        let Some(canvas) = matrix_ref() else {
+            return;
+        };
+        let Some(dataset) = dataset() else {
+            canvas.set_width(0);
+            canvas.set_height(0);
+            return;
+        };
+        let classes = row_classes(&dataset, colouring().as_ref());
+        let bounds = axis_bounds(&dataset);
+        let n = dataset.axises.len();
+        let size = n as f64 * MATRIX_CELL;
+        canvas.set_width(size as u32);
+        canvas.set_height(size as u32);
+        let ctx: CanvasRenderingContext2d =
+            canvas.get_context("2d").unwrap().unwrap().unchecked_into();
+        let inner = MATRIX_CELL - 2. * MATRIX_PADDING;
+        ctx.set_line_width(1.);
+        ctx.set_stroke_style(&"#e4e4e7".into());
+        ctx.set_font("10px sans-serif");
+        ctx.set_text_align("center");
+        ctx.set_text_baseline("middle");
+        for row in 0..n {
+            for col in 0..n {
+                let (left, top) = (col as f64 * MATRIX_CELL, row as f64 * MATRIX_CELL);
+                ctx.stroke_rect(left + 0.5, top + 0.5, MATRIX_CELL - 1., MATRIX_CELL - 1.);
+                if row == col {
+                    ctx.set_fill_style(&"#3f3f46".into());
+                    ctx.fill_text_with_max_width(
+                        &dataset.axises[col],
+                        left + MATRIX_CELL / 2.,
+                        top + MATRIX_CELL / 2.,
+                        inner,
+                    )
+                    .unwrap();
+                    continue;
+                }
+                for class in 0..dataset.classes.len() {
+                    ctx.set_fill_style(&class_color(class, 0.6).into());
+                    for ((vec, _label), _) in dataset
+                        .rows
+                        .iter()
+                        .zip(&classes)
+                        .filter(|(_, c)| **c == class)
+                    {
+                        let x = left + MATRIX_PADDING + normalize(vec[col], bounds[col]) * inner;
+                        let y = top + MATRIX_CELL
+                            - MATRIX_PADDING
+                            - normalize(vec[row], bounds[row]) * inner;
+                        ctx.fill_rect(x - 1., y - 1., 2., 2.);
+                    }
+                }
+            }
+        }
+        if let (Some(x), Some(y)) = (x_axis(), y_axis()) {
+            ctx.set_line_width(2.);
+            ctx.set_stroke_style(&"#ef4444".into());
+            ctx.stroke_rect(
+                x as f64 * MATRIX_CELL + 1.,
+                y as f64 * MATRIX_CELL + 1.,
+                MATRIX_CELL - 2.,
+                MATRIX_CELL - 2.,
+            );
+        }
+    });
+    let on_click = move |ev: ev::MouseEvent| {
+        let (Some(canvas), Some(dataset)) = (matrix_ref.get_untracked(), dataset.get_untracked())
+        else {
+            return;
+        };
+        // the canvas may be scaled down to fit
+        let scale = canvas.width() as f64 / canvas.client_width().max(1) as f64;
+        let col = (ev.offset_x() as f64 * scale / MATRIX_CELL) as usize;
+        let row = (ev.offset_y() as f64 * scale / MATRIX_CELL) as usize;
+        if row != col && row < dataset.axises.len() && col < dataset.axises.len() {
+            on_select((col, row));
+        }
+    };
+    let parallel_ref: NodeRef<html::Canvas> = create_node_ref();
+    create_effect(move |chart: Option<Option<JsValue>>| {
+        let canvas = parallel_ref()?;
+        if let Some(chart) = chart.flatten() {
+            destroy_chart(&chart);
+        }
+        let dataset = dataset()?;
+        let classes = row_classes(&dataset, colouring().as_ref());
+        let bounds = axis_bounds(&dataset);
+        let datasets = Array::new();
+        for (class, name) in dataset.classes.iter().enumerate() {
+            let obj = Object::new();
+            set(&obj, "label", &name.into());
+            set(&obj, "borderColor", &class_color(class, 0.3).into());
+            set(&obj, "backgroundColor", &class_color(class, 1.).into());
+            set(&obj, "borderWidth", &1.into());
+            set(&obj, "pointRadius", &0.into());
+            let data = Array::new();
+            for ((vec, _label), _) in dataset
+                .rows
+                .iter()
+                .zip(&classes)
+                .filter(|(_, c)| **c == class)
+            {
+                for (axis, value) in vec.iter().enumerate() {
+                    let point = Object::new();
+                    set(&point, "x", &dataset.axises[axis].as_str().into());
+                    set(&point, "y", &normalize(*value, bounds[axis]).into());
+                    data.push(&point);
+                }
+                // a null point ends the line of this row
+                let point = Object::new();
+                set(&point, "x", &dataset.axises[0].as_str().into());
+                set(&point, "y", &JsValue::NULL);
+                data.push(&point);
+            }
+            set(&obj, "data", &data);
+            datasets.push(&obj);
+        }
+        let data = Object::new();
+        set(
+            &data,
+            "labels",
+            &Array::from_iter(dataset.axises.iter().map(|axis| JsValue::from_str(axis))),
+        );
+        set(&data, "datasets", &datasets);
+        let y = Object::new();
+        set(&y, "min", &0.into());
+        set(&y, "max", &1.into());
+        let title = Object::new();
+        set(&title, "display", &true.into());
+        set(
+            &title,
+            "text",
+            &locale().pick("归一化取值", "normalized value").into(),
+        );
+        set(&y, "title", &title);
+        let scales = Object::new();
+        set(&scales, "y", &y);
+        let options = Object::new();
+        set(&options, "animation", &false.into());
+        set(&options, "scales", &scales);
+        let config = Object::new();
+        set(&config, "type", &"line".into());
+        set(&config, "data", &data);
+        set(&config, "options", &options);
+        Some(new_chart(&(*canvas.into_any()).clone(), &config))
+    });
+    let (class_name, style_val) = style_str! {
+        canvas.matrix {
+            max-width: 100%;
+            cursor: pointer;
+        }
+        .hint {
+            color: #71717a;
+            font-size: 0.875rem;
+        }
+    };
+    view! {
+        class = class_name,
+        <Style> { style_val } </Style>
+        <sl-select label=move || locale().pick("散点图矩阵与平行坐标着色", "Scatter matrix and parallel coordinates colouring") value="true" on:sl-change=move |ev: JsValue| {
+            set_by_prediction(get(&get(&ev, "target"), "value").as_string().unwrap() == "predicted");
+        }>
+            <sl-option value="true"> {move || locale().pick("真实分类", "True class")} </sl-option>
+            <sl-option value="predicted"> {move || locale().pick("预测分类", "Predicted class")} </sl-option>
+        </sl-select>
+        <Show when=move || by_prediction() && tree().is_none()>
+            <span class="hint"> {move || locale().pick("尚未训练, 按真实分类着色", "Not trained yet, coloured by true class")} </span>
+        </Show>
+        <canvas class="matrix" ref=matrix_ref on:click=on_click />
+        <div class="chart">
+            <canvas ref=parallel_ref />
+        </div>
+    }
+}
+
+fn bar_chart_config(data: &Object, stacked: bool) -> Object {
+    let x = Object::new();
+    set(&x, "stacked", &stacked.into());
+    let y = Object::new();
+    set(&y, "stacked", &stacked.into());
+    set(&y, "beginAtZero", &true.into());
+    let scales = Object::new();
+    set(&scales, "x", &x);
+    set(&scales, "y", &y);
+    let options = Object::new();
+    set(&options, "animation", &false.into());
+    set(&options, "scales", &scales);
+    let config = Object::new();
+    set(&config, "type", &"bar".into());
+    set(&config, "data", data);
+    set(&config, "options", &options);
+    config
+}
+
+// red for positive and blue for negative correlation, stronger the closer to ±1
+fn correlation_color(r: f64) -> String {
+    if r.is_nan() {
+        "transparent".to_owned()
+    } else if r >= 0. {
+        format!("rgba(239, 68, 68, {:.3})", r.abs())
+    } else {
+        format!("rgba(59, 130, 246, {:.3})", r.abs())
+    }
+}
+
+// statistics of the whole dataset, before it is split
+#[component]
+fn DatasetPanel(dataset: ReadSignal<Option<DataSet>>) -> impl IntoView {
+    let locale = expect_context::<ReadSignal<Locale>>();
+    let (axis, set_axis) = create_signal(0usize);
+    let balance_ref: NodeRef<html::Canvas> = create_node_ref();
+    create_effect(move |chart: Option<Option<JsValue>>| {
+        let canvas = balance_ref()?;
+        if let Some(chart) = chart.flatten() {
+            destroy_chart(&chart);
+        }
+        let dataset = dataset()?;
+        let counts = class_counts(&dataset);
+        let bars = Object::new();
+        set(&bars, "label", &locale().pick("点数", "points").into());
+        set(
+            &bars,
+            "data",
+            &Array::from_iter(counts.iter().map(|count| JsValue::from(*count))),
+        );
+        set(
+            &bars,
+            "backgroundColor",
+            &Array::from_iter((0..counts.len()).map(|i| JsValue::from(class_color(i, 0.5)))),
+        );
+        set(
+            &bars,
+            "borderColor",
+            &Array::from_iter((0..counts.len()).map(|i| JsValue::from(class_color(i, 1.)))),
+        );
+        set(&bars, "borderWidth", &1.into());
+        let data = Object::new();
+        set(
+            &data,
+            "labels",
+            &Array::from_iter(dataset.classes.iter().map(|class| JsValue::from_str(class))),
+        );
+        set(&data, "datasets", &Array::of1(&bars));
+        Some(new_chart(
+            &(*canvas.into_any()).clone(),
+            &bar_chart_config(&data, false),
+        ))
+    });
+    let histogram_ref: NodeRef<html::Canvas> = create_node_ref();
+    create_effect(move |chart: Option<Option<JsValue>>| {
+        let canvas = histogram_ref()?;
+        if let Some(chart) = chart.flatten() {
+            destroy_chart(&chart);
+        }
+        let dataset = dataset()?;
+        let histogram = feature_histogram(
+            &dataset,
+            axis().min(dataset.axises.len() - 1),
+            HISTOGRAM_BINS,
+        );
+        let datasets = Array::new();
+        for (i, (class, counts)) in dataset.classes.iter().zip(&histogram.counts).enumerate() {
+            let bars = Object::new();
+            set(&bars, "label", &class.into());
+            set(
+                &bars,
+                "data",
+                &Array::from_iter(counts.iter().map(|count| JsValue::from(*count))),
+            );
+            set(&bars, "backgroundColor", &class_color(i, 0.5).into());
+            set(&bars, "borderColor", &class_color(i, 1.).into());
+            set(&bars, "borderWidth", &1.into());
+            datasets.push(&bars);
+        }
+        let data = Object::new();
+        set(
+            &data,
+            "labels",
+            &Array::from_iter(
+                histogram
+                    .edges
+                    .windows(2)
+                    .map(|edge| JsValue::from(format!("{:.3} ~ {:.3}", edge[0], edge[1]))),
+            ),
+        );
+        set(&data, "datasets", &datasets);
+        Some(new_chart(
+            &(*canvas.into_any()).clone(),
+            &bar_chart_config(&data, true),
+        ))
+    });
+    let (class_name, style_val) = style_str! {
+        table {
+            width: 100%;
+            border-collapse: collapse;
+            font-size: 0.875rem;
+        }
+        th, td {
+            border-bottom: 1px solid #e4e4e7;
+            padding: 0.25rem 0.5rem;
+            text-align: left;
+        }
+        td.correlation {
+            text-align: center;
+        }
+        .panel {
+            display: flex;
+            flex-direction: column;
+            gap: 1rem;
+            overflow-x: auto;
+        }
+    };
+    view! {
+        class = class_name,
+        <Style> { style_val } </Style>
+        <Show when=move || dataset().is_some()>
+            <sl-details summary=move || locale().pick("数据集概览", "Dataset overview")>
+                <div class="panel">
+                    {move || {
+                        let dataset = dataset()?;
+                        let locale = locale();
+                        let rows = (0..dataset.axises.len())
+                            .map(|axis| {
+                                // an axis without any value is all missing
+                                let (cells, missing) = match summarize(&dataset, axis) {
+                                    Some(summary) => (
+                                        [
+                                            summary.min,
+                                            summary.max,
+                                            summary.mean,
+                                            summary.std,
+                                            summary.quartiles[0],
+                                            summary.quartiles[1],
+                                            summary.quartiles[2],
+                                        ]
+                                        .map(|value| format!("{value:.3}")),
+                                        summary.missing,
+                                    ),
+                                    None => (["-"; 7].map(str::to_owned), dataset.rows.len()),
+                                };
+                                view! {
+                                    class = class_name,
+                                    <tr>
+                                        <td> {dataset.axises[axis].clone()} </td>
+                                        {cells.into_iter().map(|cell| view! { class = class_name, <td> {cell} </td> }).collect_view()}
+                                        <td> {missing} </td>
+                                    </tr>
+                                }
+                            })
+                            .collect_view();
+                        let matrix = correlation(&dataset);
+                        let heatmap = matrix
+                            .iter()
+                            .enumerate()
+                            .map(|(a, row)| {
+                                view! {
+                                    class = class_name,
+                                    <tr>
+                                        <th> {dataset.axises[a].clone()} </th>
+                                        {row
+                                            .iter()
+                                            .map(|r| {
+                                                view! {
+                                                    class = class_name,
+                                                    <td class="correlation" style=format!("background-color: {}", correlation_color(*r))>
+                                                        {if r.is_nan() { "-".to_owned() } else { format!("{r:.2}") }}
+                                                    </td>
+                                                }
+                                            })
+                                            .collect_view()}
+                                    </tr>
+                                }
+                            })
+                            .collect_view();
+                        Some(view! {
+                            class = class_name,
+                            <table>
+                                <thead>
+                                    <tr>
+                                        <th> {locale.pick("特征", "Feature")} </th>
+                                        <th> {locale.pick("最小值", "Min")} </th>
+                                        <th> {locale.pick("最大值", "Max")} </th>
+                                        <th> {locale.pick("均值", "Mean")} </th>
+                                        <th> {locale.pick("标准差", "Std")} </th>
+                                        <th> "25%" </th>
+                                        <th> {locale.pick("中位数", "Median")} </th>
+                                        <th> "75%" </th>
+                                        <th> {locale.pick("缺失数", "Missing")} </th>
+                                    </tr>
+                                </thead>
+                                <tbody> {rows} </tbody>
+                            </table>
+                            <table>
+                                <thead>
+                                    <tr>
+                                        <th> {locale.pick("相关系数", "Correlation")} </th>
+                                        {dataset.axises.iter().map(|axis| view! { class = class_name, <th> {axis.clone()} </th> }).collect_view()}
+                                    </tr>
+                                </thead>
+                                <tbody> {heatmap} </tbody>
+                            </table>
+                        })
+                    }}
+                    <div class="chart">
+                        <canvas ref=balance_ref />
+                    </div>
+                    <sl-select label=move || locale().pick("分类直方图: 特征", "Histogram by class: feature") prop:value=move || axis().to_string()
+                        on:sl-change=move |ev: JsValue| {
+                            set_axis(get(&get(&ev, "target"), "value").as_string().unwrap().parse().unwrap_or(0));
+                        }>
+                        {move || {
+                            dataset().map(|dataset| {
+                                dataset
+                                    .axises
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(i, axis)| view! { <sl-option value=i.to_string()> {axis.clone()} </sl-option> })
+                                    .collect_view()
+                            })
+                        }}
+                    </sl-select>
+                    <div class="chart">
+                        <canvas ref=histogram_ref />
+                    </div>
+                </div>
+            </sl-details>
+        </Show>
+    }
+}
+
+// train and test accuracy while growing the training set or sweeping a hyperparameter
+#[component]
+fn AccuracyCurve(
+    dataset: ReadSignal<Option<DataSet>>,
+    hyperparameters: ReadSignal<Hyperparameters>,
+    // of the train/test split
+    seed: StoredValue<u64>,
+    log_ref: NodeRef<html::Custom>,
+) -> impl IntoView {
+    let locale = expect_context::<ReadSignal<Locale>>();
+    let (kind, set_kind) = create_signal(CurveKind::Learning);
+    let (points, set_points) = create_signal(None::<(CurveKind, Vec<CurvePoint>)>);
+    let (drawing, set_drawing) = create_signal(None::<(usize, usize)>);
+    // the curve being drawn, events of an older one are dropped
+    let draw_id = store_value(0u64);
+    let draw_kind = store_value(CurveKind::Learning);
+    let draw_begin = store_value(0f64);
+    // its own worker, so drawing doesn't wait for or block training
+    let on_event = move |event: TrainEvent| match event {
+        TrainEvent::Drawing { id, done, total } => {
+            if id == draw_id.get_value() {
+                set_drawing(Some((done, total)));
+            }
+        }
+        TrainEvent::Drawn { id, points } => {
+            if id != draw_id.get_value() {
+                return;
+            }
+            set_drawing(None);
+            let duration = (0.001f64).max(timestamp() - draw_begin.get_value());
+            log!(
+                log_ref,
+                locale.get_untracked(),
+                "曲线绘制完毕, 共训练 {} 棵树, 用时 {duration:.3} 秒",
+                "Curve drawn, {} trees trained in {duration:.3} s",
+                points.len()
+            );
+            set_points(Some((draw_kind.get_value(), points)));
+        }
+        _ => {}
+    };
+    let worker = store_value(spawn_worker(on_event));
+    on_cleanup(move || worker.with_value(Worker::terminate));
+    create_effect(move |_| {
+        dataset.track();
+        // a curve of the previous dataset is abandoned
+        if drawing.get_untracked().is_some() {
+            worker.with_value(Worker::terminate);
+            worker.set_value(spawn_worker(on_event));
+            draw_id.update_value(|id| *id += 1);
+            set_drawing(None);
+        }
+        set_points(None);
+    });
+    let draw = move |_| {
+        let Some(dataset) = dataset.get_untracked() else {
+            return;
+        };
+        let hyperparameters = hyperparameters.get_untracked();
+        if !hyperparameters.split_search.is_valid() || drawing.get_untracked().is_some() {
+            return;
+        }
+        let seed = seed.get_value();
+        let (train_data, test_data) = dataset.split(dataset.rows.len() / 5, seed);
+        let kind = kind.get_untracked();
+        draw_id.update_value(|id| *id += 1);
+        draw_kind.set_value(kind);
+        draw_begin.set_value(timestamp());
+        set_drawing(Some((0, kind.xs().len())));
+        let request = CurveRequest {
+            id: draw_id.get_value(),
+            kind,
+            axises: dataset.mapped_axis.clone(),
+            classes: dataset.mapped_class.clone(),
+            train_data,
+            test_data,
+            seed,
+            hyperparameters,
+        };
+        worker.with_value(|worker| post_curve(worker, request));
+    };
+    let chart_ref: NodeRef<html::Canvas> = create_node_ref();
+    create_effect(move |chart: Option<Option<JsValue>>| {
+        let canvas = chart_ref()?;
+        let locale = locale();
+        let datasets = Array::new();
+        let x_title = match points() {
+            Some((kind, points)) => {
+                let line = |accuracy: fn(&CurvePoint) -> f64| {
+                    points
+                        .iter()
+                        .map(|point| (point.x, accuracy(point) * 100.))
+                        .collect::<Vec<_>>()
+                };
+                datasets.push(&into_line_dataset(
+                    locale.pick("训练集正确率", "train accuracy"),
+                    "#3b82f6",
+                    2.,
+                    &[line(|p| p.train_accuracy)],
+                ));
+                datasets.push(&into_line_dataset(
+                    locale.pick("测试集正确率", "test accuracy"),
+                    "#ef4444",
+                    2.,
+                    &[line(|p| p.test_accuracy)],
+                ));
+                match kind {
+                    CurveKind::Learning => locale.pick("训练集比例", "training set fraction"),
+                    CurveKind::Validation(Parameter::MaxDepth) => {
+                        locale.pick("最大深度", "max depth")
+                    }
+                    CurveKind::Validation(Parameter::MinElementsToSplit) => {
+                        locale.pick("最小分裂点数", "min points to split")
+                    }
+                }
+            }
+            None => "",
+        };
+        // the axis title changes with the curve, so the chart is rebuilt
+        if let Some(chart) = chart.flatten() {
+            destroy_chart(&chart);
+        }
+        let config = line_chart_config(x_title, locale.pick("正确率 (%)", "accuracy (%)"));
+        let data = Object::new();
+        set(&data, "datasets", &datasets);
+        set(&config, "data", &data);
+        Some(new_chart(&(*canvas.into_any()).clone(), &config))
+    });
+    let (class_name, style_val) = style_str! {
+        .controls {
+            display: flex;
+            flex-direction: row;
+            align-items: end;
+            gap: 1rem;
+        }
+        .progress {
+            display: flex;
+            flex-direction: column;
+            gap: 0.25rem;
+        }
+    };
+    view! {
+        class = class_name,
+        <Style> { style_val } </Style>
+        <div class="controls">
+            <sl-select label=move || locale().pick("正确率曲线", "Accuracy curve") value="learning" on:sl-change=move |ev: JsValue| {
+                match get(&get(&ev, "target"), "value").as_string().unwrap().as_str() {
+                    "max-depth" => set_kind(CurveKind::Validation(Parameter::MaxDepth)),
+                    "min-elements-to-split" => set_kind(CurveKind::Validation(Parameter::MinElementsToSplit)),
+                    _ => set_kind(CurveKind::Learning),
+                }
+            }>
+                <sl-option value="learning"> {move || locale().pick("学习曲线", "Learning curve")} </sl-option>
+                <sl-option value="max-depth"> {move || locale().pick("验证曲线: 最大深度", "Validation curve: max depth")} </sl-option>
+                <sl-option value="min-elements-to-split"> {move || locale().pick("验证曲线: 最小分裂点数", "Validation curve: min points to split")} </sl-option>
+            </sl-select>
+            <sl-button disabled=move || dataset().is_none() || !hyperparameters().split_search.is_valid() loading=move || drawing().is_some() on:click=draw> {move || locale().pick("绘制", "Draw")} </sl-button>
+        </div>
+        <Show when=move || drawing().is_some()>
+            <div class="progress">
+                <sl-progress-bar value=move || drawing().map_or(0., |(done, total)| done as f64 / total.max(1) as f64 * 100.)></sl-progress-bar>
+                <span>
+                    {move || drawing().map(|(done, total)| tr!(locale(), "已训练 {done} / {total} 棵树", "{done} / {total} trees trained"))}
+                </span>
+            </div>
+        </Show>
+        <div class="chart">
+            <canvas ref=chart_ref />
+        </div>
+    }
+}
+
+// one-vs-rest curves of every class on the test set, with their area
+#[derive(Debug, Clone)]
+struct ClassCurves {
+    roc: Vec<(Vec<(f64, f64)>, f64)>,
+    pr: Vec<(Vec<(f64, f64)>, f64)>,
+    reliability: Vec<ReliabilityBin>,
+}
+
+// ROC and precision-recall curves and a reliability diagram of the leaf class
+// frequencies of a tree, calibrated on out-of-fold predictions of the training set
+#[component]
+fn RankingCurves(
+    dataset: ReadSignal<Option<DataSet>>,
+    hyperparameters: ReadSignal<Hyperparameters>,
+    // of the train/test split
+    seed: StoredValue<u64>,
+    log_ref: NodeRef<html::Custom>,
+) -> impl IntoView {
+    let locale = expect_context::<ReadSignal<Locale>>();
+    let (calibration, set_calibration) = create_signal(Calibration::None);
+    let (curves, set_curves) = create_signal(None::<ClassCurves>);
+    create_effect(move |_| {
+        dataset.track();
+        set_curves(None);
+    });
+    let draw = move |_| {
+        let Some(dataset) = dataset.get_untracked() else {
+            return;
+        };
+        let begin = timestamp();
+        let seed = seed.get_value();
+        let (train_data, test_data) = dataset.split(dataset.rows.len() / 5, seed);
+        let hyperparameters = hyperparameters.get_untracked();
+        let classes = &dataset.mapped_class;
+        let position = |class: &Class| classes.iter().position(|c| c == class).unwrap();
+        let tree = train(
+            &dataset.mapped_axis,
+            classes,
+            train_data.clone(),
+            &hyperparameters,
+        );
+        let leaves = LeafProbabilities::fit(&tree, classes, &train_data);
+        let mut probabilities: Vec<Vec<f64>> = test_data
+            .iter()
+            .map(|(dp, _class)| leaves.probabilities(dp))
+            .collect();
+        let labels: Vec<usize> = test_data
+            .iter()
+            .map(|(_dp, class)| position(class))
+            .collect();
+        let raw_brier = brier_score(&probabilities, &labels);
+        let calibration = calibration.get_untracked();
+        if calibration != Calibration::None {
+            let out_of_fold = out_of_fold_probabilities(
+                &dataset.mapped_axis,
+                classes,
+                &train_data,
+                SEARCH_FOLDS,
+                seed,
+                &hyperparameters,
+            );
+            let train_labels: Vec<usize> = train_data
+                .iter()
+                .map(|(_dp, class)| position(class))
+                .collect();
+            let calibrator = Calibrator::fit(calibration, &out_of_fold, &train_labels);
+            probabilities = probabilities.iter().map(|p| calibrator.apply(p)).collect();
+        }
+        let mut roc = Vec::new();
+        let mut pr = Vec::new();
+        for class in 0..classes.len() {
+            let scores: Vec<f64> = probabilities.iter().map(|p| p[class]).collect();
+            let positives: Vec<bool> = labels.iter().map(|label| *label == class).collect();
+            let curve = roc_curve(&scores, &positives);
+            let area = auc(&curve);
+            roc.push((curve, area));
+            let curve = pr_curve(&scores, &positives);
+            let area = average_precision(&curve);
+            pr.push((curve, area));
+        }
+        let locale = locale.get_untracked();
+        let duration = (0.001f64).max(timestamp() - begin);
+        let mean_auc = roc.iter().map(|(_curve, area)| area).sum::<f64>() / roc.len() as f64;
+        log!(
+            log_ref,
+            locale,
+            "排序曲线绘制完毕, 平均 ROC AUC {mean_auc:.3}, Brier 分数 {raw_brier:.4} → {:.4}, 用时 {duration:.3} 秒",
+            "Ranking curves drawn, mean ROC AUC {mean_auc:.3}, Brier score {raw_brier:.4} → {:.4}, took {duration:.3} s",
+            brier_score(&probabilities, &labels)
+        );
+        set_curves(Some(ClassCurves {
+            roc,
+            pr,
+            reliability: reliability(&probabilities, &labels, RELIABILITY_BINS),
+        }));
+    };
+    let diagonal = |label: &str| into_line_dataset(label, "#a1a1aa", 1., &[[(0., 0.), (1., 1.)]]);
+    // the curve of every class, labelled with its area
+    let class_curves = move |curves: &[(Vec<(f64, f64)>, f64)], datasets: &Array| {
+        let dataset = dataset.get_untracked().unwrap();
+        for (i, (curve, area)) in curves.iter().enumerate() {
+            let label = format!("{} ({area:.3})", dataset.classes[i]);
+            datasets.push(&into_line_dataset(
+                &label,
+                &class_color(i, 1.),
+                2.,
+                &[curve],
+            ));
+        }
+    };
+    let redraw = |chart: Option<Option<JsValue>>,
+                  canvas: HtmlElement<html::Canvas>,
+                  x_title: &str,
+                  y_title: &str,
+                  datasets: Array| {
+        if let Some(chart) = chart.flatten() {
+            destroy_chart(&chart);
+        }
+        let config = line_chart_config(x_title, y_title);
+        let data = Object::new();
+        set(&data, "datasets", &datasets);
+        set(&config, "data", &data);
+        Some(new_chart(&(*canvas.into_any()).clone(), &config))
+    };
+    let roc_ref: NodeRef<html::Canvas> = create_node_ref();
+    create_effect(move |chart: Option<Option<JsValue>>| {
+        let canvas = roc_ref()?;
+        let locale = locale();
+        let datasets = Array::new();
+        if let Some(curves) = curves() {
+            class_curves(&curves.roc, &datasets);
+            datasets.push(&diagonal(locale.pick("随机", "chance")));
+        }
+        redraw(
+            chart,
+            canvas,
+            locale.pick("假正例率", "false positive rate"),
+            locale.pick(
+                "真正例率 (ROC, 一对其余)",
+                "true positive rate (ROC, one-vs-rest)",
+            ),
+            datasets,
+        )
+    });
+    let pr_ref: NodeRef<html::Canvas> = create_node_ref();
+    create_effect(move |chart: Option<Option<JsValue>>| {
+        let canvas = pr_ref()?;
+        let locale = locale();
+        let datasets = Array::new();
+        if let Some(curves) = curves() {
+            class_curves(&curves.pr, &datasets);
+        }
+        redraw(
+            chart,
+            canvas,
+            locale.pick("召回率", "recall"),
+            locale.pick(
+                "精确率 (括号内为平均精确率)",
+                "precision (average precision in brackets)",
+            ),
+            datasets,
+        )
+    });
+    let reliability_ref: NodeRef<html::Canvas> = create_node_ref();
+    create_effect(move |chart: Option<Option<JsValue>>| {
+        let canvas = reliability_ref()?;
+        let locale = locale();
+        let datasets = Array::new();
+        if let Some(curves) = curves() {
+            let points: Vec<(f64, f64)> = curves
+                .reliability
+                .iter()
+                .map(|bin| (bin.confidence, bin.accuracy))
+                .collect();
+            let line = into_line_dataset(
+                locale.pick("预测类别", "predicted class"),
+                "#3b82f6",
+                2.,
+                &[points],
+            );
+            set(&line, "pointRadius", &4.into());
+            datasets.push(&line);
+            datasets.push(&diagonal(locale.pick("完美校准", "perfectly calibrated")));
+        }
+        redraw(
+            chart,
+            canvas,
+            locale.pick("平均预测概率", "mean predicted probability"),
+            locale.pick("实际正确率", "observed accuracy"),
+            datasets,
+        )
+    });
+    let (class_name, style_val) = style_str! {
+        .controls {
+            display: flex;
+            flex-direction: row;
+            align-items: end;
+            gap: 1rem;
+        }
+        .charts {
+            display: grid;
+            grid-template-columns: repeat(auto-fit, minmax(18rem, 1fr));
+            gap: 1rem;
+        }
+    };
+    view! {
+        class = class_name,
+        <Style> { style_val } </Style>
+        <div class="controls">
+            <sl-select label=move || locale().pick("概率校准", "Probability calibration") value="none" on:sl-change=move |ev: JsValue| {
+                match get(&get(&ev, "target"), "value").as_string().unwrap().as_str() {
+                    "platt" => set_calibration(Calibration::Platt),
+                    "isotonic" => set_calibration(Calibration::Isotonic),
+                    _ => set_calibration(Calibration::None),
+                }
+            }>
+                <sl-option value="none"> {move || locale().pick("无 (叶节点类别频率)", "None (leaf class frequencies)")} </sl-option>
+                <sl-option value="platt"> {move || locale().pick("Platt 缩放", "Platt scaling")} </sl-option>
+                <sl-option value="isotonic"> {move || locale().pick("保序回归", "Isotonic regression")} </sl-option>
+            </sl-select>
+            <sl-button disabled=move || dataset().is_none() || !hyperparameters().split_search.is_valid() on:click=draw> {move || locale().pick("绘制 ROC / PR 曲线", "Draw ROC / PR curves")} </sl-button>
+        </div>
+        <div class="charts">
+            <canvas ref=roc_ref />
+            <canvas ref=pr_ref />
+            <canvas ref=reliability_ref />
+        </div>
+    }
+}
+
+#[component]
+pub fn Main() -> impl IntoView {
+    let saved = session::load();
+    let browser_locale = window()
+        .navigator()
+        .language()
+        .and_then(|code| Locale::from_code(&code));
+    let (locale, set_locale) = create_signal(
+        saved
+            .as_ref()
+            .map(|saved| saved.locale)
+            .or(browser_locale)
+            .unwrap_or_default(),
+    );
+    provide_context(locale);
+    // as loaded, `dataset` has the names translated to `locale`
+    let raw_dataset = store_value(None::<DataSet>);
+    let (dataset, set_dataset) = create_signal(None::<DataSet>);
+    let (train_data, set_train_data) = create_signal(None::<Vec<(DataPoint, Class)>>);
+    let (train_classify_data, set_train_classify_data) = create_signal(None::<Vec<DataPoint>>);
+    let (classify_data, set_classify_data) = create_signal(None::<LabelledData>);
+    let (tree, set_tree) = create_signal(None::<DecisionTree>);
+    let (rules, set_rules) = create_signal(None::<Vec<Rule>>);
+    let (result, set_result) = create_signal(None::<DataSet>);
+    let log_ref: NodeRef<html::Custom> = create_node_ref();
+    let x_ref: NodeRef<html::Custom> = create_node_ref();
+    let y_ref: NodeRef<html::Custom> = create_node_ref();
+    let export_ref: NodeRef<html::Custom> = create_node_ref();
+    let (training, set_training) = create_signal(false);
+    let train_id = store_value(0u64);
+    let train_begin = store_value(0f64);
+    // the bundled dataset shown, and the seed of its train/test split
+    let (source, set_source) = create_signal(None::<String>);
+    let seed = store_value(RANDOM_SEED);
+    // used by the next training or as the base of a search
+    let (hyperparameters, set_hyperparameters) = create_signal(Hyperparameters::default());
+    let train_hyperparameters = store_value(Hyperparameters::default());
+    // the hyperparameters `tree` was grown with
+    let (tree_hyperparameters, set_tree_hyperparameters) =
+        create_signal(Hyperparameters::default());
+    let (search_progress, set_search_progress) = create_signal(None::<(usize, usize)>);
+    let (search_results, set_search_results) = create_signal(None::<Vec<SearchResult>>);
+    let (random_search, set_random_search) = create_signal(false);
+    // every tree trained on the current dataset, numbered from 1
+    let (models, set_models) = create_signal(Vec::<RegisteredModel>::new());
+    let model_id = store_value(0usize);
+    let (progress, set_progress) = create_signal(None::<Progress>);
+    // predictions of `tree` on the test set, shown on the chart instead of the dataset
+    let (predictions, set_predictions) = create_signal(None::<Vec<Prediction>>);
+    let (show_predictions, set_show_predictions) = create_signal(false);
+    create_effect(move |_| {
+        tree.track();
+        set_predictions(None);
+        set_show_predictions(false);
+    });
+    // isolation forest scores of every row, computed while they are shown
+    let (show_anomalies, set_show_anomalies) = create_signal(false);
+    let (anomaly_threshold, set_anomaly_threshold) = create_signal(ANOMALY_THRESHOLD);
+    let anomalies = create_memo(move |_| {
+        let dataset = dataset().filter(|_| show_anomalies())?;
+        let data: Vec<DataPoint> = dataset
+            .labelled()
+            .into_iter()
+            .map(|(dp, _class)| dp)
+            .collect();
+        Some(anomaly_scores(
+            &dataset.mapped_axis,
+            &data,
+            seed.get_value(),
+        ))
+    });
+    // returns the test accuracy of the tree
+    let register = move |tree: &DecisionTree,
+                         hyperparameters: Hyperparameters,
+                         train_accuracy: f64,
+                         duration: f64| {
+        model_id.update_value(|id| *id += 1);
+        let model = RegisteredModel {
+            id: model_id.get_value(),
+            tree: tree.clone(),
+            hyperparameters,
+            train_accuracy,
+            test_accuracy: accuracy(tree, &classify_data.get_untracked().unwrap()),
+            leaves: leaves(tree),
+            depth: depth(tree),
+            duration,
+        };
+        let test_accuracy = model.test_accuracy;
+        set_models.update(|models| models.push(model));
+        test_accuracy
+    };
+    // logs and shows a tree from the worker, returns its accuracy on the training set
+    let show_tree = move |tree: DecisionTree, hyperparameters: Hyperparameters| {
+        let dataset = dataset.get_untracked().unwrap();
+        let result = classify(&tree, train_classify_data.get_untracked().unwrap());
+        log!(
+            log_ref,
+            locale.get_untracked(),
+            "决策树:\n{}",
+            "Decision tree:\n{}",
+            display_tree(&dataset, &tree, 0)
+        );
+        set_rules(Some(extract_rules(
+            &tree,
+            &train_data.get_untracked().unwrap(),
+        )));
+        set_tree(Some(tree));
+        set_tree_hyperparameters(hyperparameters);
+        assess(&dataset, &result)
+    };
+    let on_train_event = move |event: TrainEvent| match event {
+        TrainEvent::Progress { id, progress } => {
+            if id == train_id.get_value() {
+                set_progress(Some(progress));
+            }
+        }
+        TrainEvent::Trained { id, tree } => {
+            if id != train_id.get_value() {
+                return;
+            }
+            set_training(false);
+            set_progress(None);
+            let hyperparameters = train_hyperparameters.get_value();
+            let correct_rate = show_tree(tree.clone(), hyperparameters);
+            let duration = (0.001f64).max(timestamp() - train_begin.get_value());
+            let locale = locale.get_untracked();
+            log!(
+                log_ref,
+                locale,
+                "训练完毕 ({}), 训练集分类正确率 {:.3}%, 用时 {duration:.3} 秒",
+                "Trained ({}), train accuracy {:.3}%, took {duration:.3} s",
+                display_method(&hyperparameters, locale),
+                correct_rate * 100.
+            );
+            let test_accuracy = register(&tree, hyperparameters, correct_rate, duration);
+            let other = models.with_untracked(|models| {
+                models
+                    .iter()
+                    .rev()
+                    .find(|other| {
+                        (
+                            other.hyperparameters.algorithm,
+                            other.hyperparameters.split_search,
+                        ) != (hyperparameters.algorithm, hyperparameters.split_search)
+                    })
+                    .cloned()
+            });
+            if let Some(other) = other {
+                log!(
+                    log_ref,
+                    locale,
+                    "对比 #{} {}: 测试集正确率 {:+.3}%, 训练集正确率 {:+.3}%, 用时 {:.2} 倍",
+                    "Compared with #{} {}: test accuracy {:+.3}%, train accuracy {:+.3}%, {:.2} times the time",
+                    other.id,
+                    display_method(&other.hyperparameters, locale),
+                    (test_accuracy - other.test_accuracy) * 100.,
+                    (correct_rate - other.train_accuracy) * 100.,
+                    duration / other.duration
+                );
+            }
+        }
+        TrainEvent::Searching { id, done, total } => {
+            if id == train_id.get_value() {
+                set_search_progress(Some((done, total)));
+            }
+        }
+        TrainEvent::Searched { id, report } => {
+            if id != train_id.get_value() {
+                return;
+            }
+            set_training(false);
+            set_search_progress(None);
+            let duration = (0.001f64).max(timestamp() - train_begin.get_value());
+            let Some(best) = report.results.first().cloned() else {
+                return;
+            };
+            let locale = locale.get_untracked();
+            log!(
+                log_ref,
+                locale,
+                "搜索完毕, 共评估 {} 组超参数, 用时 {duration:.3} 秒, 最佳: {}, 交叉验证平均正确率 {:.3}% ± {:.3}%",
+                "Search finished, {} candidates scored in {duration:.3} s, best: {}, mean cross-validation accuracy {:.3}% ± {:.3}%",
+                report.results.len(),
+                display_hyperparameters(&best.hyperparameters, locale),
+                best.mean * 100.,
+                best.std * 100.
+            );
+            set_search_results(Some(report.results));
+            set_hyperparameters(best.hyperparameters);
+            let correct_rate = show_tree(report.tree.clone(), best.hyperparameters);
+            register(&report.tree, best.hyperparameters, correct_rate, duration);
+            log!(
+                log_ref,
+                locale,
+                "已用最佳超参数重新训练, 训练集分类正确率 {:.3}%",
+                "Retrained with the best hyperparameters, train accuracy {:.3}%",
+                correct_rate * 100.
+            );
+        }
+        // curves are drawn by the worker of `AccuracyCurve`
+        TrainEvent::Drawing { .. } | TrainEvent::Drawn { .. } => {}
+    };
+    let worker = store_value(spawn_worker(on_train_event));
+    on_cleanup(move || worker.with_value(Worker::terminate));
+    let growth = store_value(None::<Growth>);
+    let (step, set_step) = create_signal(None::<Step>);
+    let autoplay = store_value(None::<IntervalHandle>);
+    let (playing, set_playing) = create_signal(false);
+    let stop = move || {
+        autoplay.update_value(|handle| {
+            if let Some(handle) = handle.take() {
+                handle.clear();
+            }
+        });
+        set_playing(false);
+    };
+    let reset_growth = move || {
+        stop();
+        growth.set_value(None);
+        set_step(None);
+    };
+    on_cleanup(stop);
+    // a growth is continued only with the hyperparameters it started with
+    create_effect(move |_| {
+        hyperparameters.track();
+        reset_growth();
+    });
+    // expands one node, starting over once the tree is complete, returns
+    // whether there are nodes left
+    let advance = move || {
+        let Some(dataset) = dataset.get_untracked() else {
+            return false;
+        };
+        if growth.with_value(|growth| growth.as_ref().is_none_or(Growth::is_finished)) {
+            // the steps are never pruned
+            let hyperparameters = Hyperparameters {
+                ccp_alpha: 0.,
+                ..hyperparameters.get_untracked()
+            };
+            growth.set_value(Some(Growth::new(
+                &dataset.mapped_axis,
+                &dataset.mapped_class,
+                train_data.get_untracked().unwrap(),
+                &hyperparameters,
+            )));
+            set_rules(None);
+            set_tree_hyperparameters(hyperparameters);
+            log!(
+                log_ref,
+                locale.get_untracked(),
+                "开始逐步生长决策树 ({})",
+                "Growing the tree step by step ({})",
+                display_hyperparameters(&hyperparameters, locale.get_untracked())
+            );
+        }
+        let impurity_name = display_impurity(
+            tree_hyperparameters.get_untracked().impurity,
+            locale.get_untracked(),
+        );
+        let Some((next, tree, finished)) = growth
+            .try_update_value(|growth| {
+                let growth = growth.as_mut().unwrap();
+                let step = growth.next()?;
+                Some((step, growth.tree(), growth.is_finished()))
+            })
+            .flatten()
+        else {
+            return false;
+        };
+        let axis_name = |axis: &Axis| dataset.axis_map.get_by_right(axis).unwrap();
+        match next.criterion {
+            Some(c) => {
+                let impurity = next
+                    .curves
+                    .iter()
+                    .find(|curve| curve.axis == c.axis)
+                    .and_then(|curve| curve.points.iter().find(|(split, _)| *split == c.split))
+                    .unwrap()
+                    .1;
+                log!(
+                    log_ref,
+                    locale.get_untracked(),
+                    "节点 #{} (深度 {}, {impurity_name} {:.4}): 按 {} < {:.3} 分裂, 加权{impurity_name}降至 {impurity:.4}",
+                    "Node #{} (depth {}, {impurity_name} {:.4}): split on {} < {:.3}, weighted {impurity_name} down to {impurity:.4}",
+                    next.node,
+                    next.depth,
+                    next.impurity,
+                    axis_name(&c.axis),
+                    c.split
+                );
+            }
+            None => log!(
+                log_ref,
+                locale.get_untracked(),
+                "节点 #{} (深度 {}, {impurity_name} {:.4}): 成为叶子, 分类为 {}",
+                "Node #{} (depth {}, {impurity_name} {:.4}): becomes a leaf of class {}",
+                next.node,
+                next.depth,
+                next.impurity,
+                dataset.class_map.get_by_right(&next.class).unwrap()
+            ),
+        }
+        set_step(Some(next));
+        if finished {
+            let train_data = train_data.get_untracked().unwrap();
+            set_rules(Some(extract_rules(&tree, &train_data)));
+            log!(
+                log_ref,
+                locale.get_untracked(),
+                "逐步生长完毕, 训练集分类正确率 {:.3}%",
+                "Growth finished, train accuracy {:.3}%",
+                accuracy(&tree, &train_data) * 100.
+            );
+        }
+        set_tree(Some(tree));
+        !finished
+    };
+    let (x_axis, set_x_axis) = create_signal(None::<usize>);
+    let (y_axis, set_y_axis) = create_signal(None::<usize>);
+    create_effect(move |_| {
+        let Some(dataset) = dataset() else {
+            return;
+        };
+        let x = x_ref.get_untracked().unwrap();
+        let y = y_ref.get_untracked().unwrap();
+        let nodes: Vec<_> = dataset
+            .axises
+            .iter()
+            .enumerate()
+            .map(|(i, label)| {
+                let element = document().create_element("sl-option").unwrap();
+                element.set_attribute("value", &i.to_string()).unwrap();
+                element.set_text_content(Some(label));
+                element
+            })
+            .collect();
+        let element = Element::from((*x.into_any()).clone());
+        element.replace_children_with_node(&Array::from_iter(nodes.clone()));
+        set_property(
+            &element,
+            "value",
+            &Some(
+                x_axis
+                    .get_untracked()
+                    .map_or_else(String::new, |x| x.to_string())
+                    .into(),
+            ),
+        );
+        let nodes: Vec<_> = dataset
+            .axises
+            .iter()
+            .enumerate()
+            .map(|(i, label)| {
+                let element = document().create_element("sl-option").unwrap();
+                element.set_attribute("value", &i.to_string()).unwrap();
+                element.set_text_content(Some(label));
+                element
+            })
+            .collect();
+        let element = Element::from((*y.into_any()).clone());
+        element.replace_children_with_node(&Array::from_iter(nodes.clone()));
+        set_property(
+            &element,
+            "value",
+            &Some(
+                y_axis
+                    .get_untracked()
+                    .map_or_else(String::new, |y| y.to_string())
+                    .into(),
+            ),
+        );
+    });
+    create_effect(move |previous: Option<Locale>| {
+        let locale = locale();
+        if previous.is_some_and(|previous| previous != locale) {
+            raw_dataset.with_value(|raw| {
+                if let Some(raw) = raw {
+                    set_dataset(Some(raw.rename(|name| dataset_name(locale, name))));
+                }
+            });
+        }
+        locale
+    });
+    let chart_ref: NodeRef<html::Canvas> = create_node_ref();
+    let dataset_not_found_alert: NodeRef<html::Custom> = create_node_ref();
+    let x_y_same_alert: NodeRef<html::Custom> = create_node_ref();
+    let tree_not_trained_alert: NodeRef<html::Custom> = create_node_ref();
+    // a shared link or saved session can carry any number of bins
+    let bins_alert: NodeRef<html::Custom> = create_node_ref();
+    create_effect(move |previous_chart: Option<Option<JsValue>>| {
+        let chart = chart_ref()?;
+        if previous_chart.is_none() {
+            x_axis.track();
+            let chart = (*chart.into_any()).clone();
+            let config = Object::new();
+            set(&config, "type", &"scatter".into());
+            set(
+                &config,
+                "options",
+                &{
+                    let obj = Object::new();
+                    set(
+                        &obj,
+                        "scales",
+                        &{
+                            let obj = Object::new();
+                            set(
+                                &obj,
+                                "x",
+                                &{
+                                    let obj = Object::new();
+                                    set(&obj, "type", &"linear".into());
+                                    set(&obj, "position", &"bottom".into());
+                                    obj
+                                }
+                                .into(),
+                            );
+                            obj
+                        }
+                        .into(),
+                    );
+                    obj
+                }
+                .into(),
+            );
+            let callbacks = Object::new();
+            set(&callbacks, "label", &point_tooltip());
+            let tooltip = Object::new();
+            set(&tooltip, "callbacks", &callbacks);
+            let plugins = Object::new();
+            set(&plugins, "tooltip", &tooltip);
+            set(&get(&config, "options"), "plugins", &plugins);
+            let chart = new_chart(&chart, &config);
+            return Some(chart);
+        };
+        let previous_chart = previous_chart.unwrap();
+        let Some(x_axis) = x_axis() else {
+            return previous_chart;
+        };
+        let Some(y_axis) = y_axis() else {
+            return previous_chart;
+        };
+        if x_axis == y_axis {
+            toast_alert(x_y_same_alert);
+            return previous_chart;
+        }
+        let previous_chart = previous_chart.unwrap();
+        let dataset = dataset().unwrap();
+        let locale = locale();
+        let data = match (anomalies(), predictions().filter(|_| show_predictions())) {
+            (Some(scores), _) => into_anomaly_dataset(
+                x_axis,
+                y_axis,
+                &dataset,
+                &scores,
+                anomaly_threshold(),
+                locale,
+            ),
+            (None, Some(predictions)) => {
+                into_prediction_dataset(x_axis, y_axis, &dataset, &predictions, locale)
+            }
+            (None, None) => into_chart_dataset(x_axis, y_axis, &dataset.classes, &dataset.rows),
+        };
+        let datasets: Array = get(&data, "datasets").into();
+        let bounds = data_bounds(x_axis, y_axis, &dataset.rows);
+        if let Some(tree) = tree() {
+            let means: Vec<f64> = (0..dataset.axises.len())
+                .map(|axis| summarize(&dataset, axis).map_or(0., |summary| summary.mean))
+                .collect();
+            let mut lines = Vec::new();
+            partition_lines(
+                x_axis,
+                y_axis,
+                &tree,
+                &rectangle(bounds),
+                &means,
+                &mut lines,
+            );
+            datasets.push(&into_line_dataset(
+                locale.pick("分割线", "partition"),
+                "#71717a",
+                1.,
+                &lines,
+            ));
+        }
+        if let Some(step) = step() {
+            let region = region_bounds(x_axis, y_axis, &step.conditions, bounds);
+            let lines: Vec<_> = step
+                .criterion
+                .and_then(|c| criterion_line(x_axis, y_axis, &c))
+                .and_then(|line| chord(&rectangle(region), line))
+                .into_iter()
+                .collect();
+            datasets.push(&into_line_dataset(
+                locale.pick("新分割线", "new split"),
+                "#ef4444",
+                3.,
+                &lines,
+            ));
+        }
+        set(&previous_chart, "data", &data);
+        update_chart(&previous_chart);
+        Some(previous_chart)
+    });
+    let show_axes = move |x: Option<usize>, y: Option<usize>| {
+        for (select, axis) in [(x_ref, x), (y_ref, y)] {
+            set_property(
+                &select.get_untracked().unwrap().into_any(),
+                "value",
+                &Some(
+                    axis.map_or_else(String::new, |axis| axis.to_string())
+                        .into(),
+                ),
+            );
+        }
+        set_x_axis(x);
+        set_y_axis(y);
+    };
+    // picking a thumbnail of the scatter-plot matrix shows it on the main chart
+    let select_pair = move |(x, y): (usize, usize)| {
+        show_axes(Some(x), Some(y));
+        if let Some(chart) = chart_ref.get_untracked() {
+            chart.scroll_into_view();
+        }
+    };
+    // brings a registered tree back as the current one
+    let show_model = move |id: usize| {
+        let Some(model) =
+            models.with_untracked(|models| models.iter().find(|model| model.id == id).cloned())
+        else {
+            return;
+        };
+        reset_growth();
+        let locale = locale.get_untracked();
+        log!(
+            log_ref,
+            locale,
+            "显示模型 #{id} ({})",
+            "Showing model #{id} ({})",
+            display_method(&model.hyperparameters, locale)
+        );
+        show_tree(model.tree, model.hyperparameters);
+    };
+    let open_dataset = move |name: String| {
+        let begin = timestamp();
+        set_x_axis(None);
+        set_y_axis(None);
+        set_tree(None);
+        set_rules(None);
+        reset_growth();
+        set_models(Vec::new());
+        model_id.set_value(0);
+        set_search_results(None);
+        train_id.update_value(|id| *id += 1);
+        set_training(false);
+        set_progress(None);
+        set_search_progress(None);
+        let csv = load_csv(name.clone());
+        let raw = load_dataset(csv).unwrap();
+        let (train_data, train_classify_data, classify_data) =
+            split_train_classify_dataset(&raw, seed.get_value());
+        let locale = locale.get_untracked();
+        set_source(Some(name));
+        set_dataset(Some(raw.rename(|name| dataset_name(locale, name))));
+        raw_dataset.set_value(Some(raw));
+        let duration = (0.001f64).max(timestamp() - begin);
+        log!(
+            log_ref,
+            locale,
+            "数据集加载完毕, 训练集点数 {}, 测试集点数 {}, 用时 {duration:.3} 秒",
+            "Dataset loaded, {} training points, {} test points, took {duration:.3} s",
+            train_data.len(),
+            classify_data.len()
+        );
+        set_train_data(Some(train_data));
+        set_train_classify_data(Some(train_classify_data));
+        set_classify_data(Some(classify_data));
+    };
+    let start_training = move || {
+        if training.get_untracked() {
+            return;
+        }
+        let Some(dataset) = dataset.get_untracked() else {
+            toast_alert(dataset_not_found_alert);
+            return;
+        };
+        if !hyperparameters.get_untracked().split_search.is_valid() {
+            toast_alert(bins_alert);
+            return;
+        }
+        reset_growth();
+        train_begin.set_value(timestamp());
+        train_id.update_value(|id| *id += 1);
+        set_training(true);
+        let hyperparameters = hyperparameters.get_untracked();
+        train_hyperparameters.set_value(hyperparameters);
+        let request = TrainRequest {
+            id: train_id.get_value(),
+            axises: dataset.mapped_axis.clone(),
+            classes: dataset.mapped_class.clone(),
+            data: train_data.get_untracked().unwrap(),
+            hyperparameters,
+        };
+        worker.with_value(|worker| post_train(worker, request));
+    };
+    // a shared URL trains again with its settings, otherwise the saved session is shown as it was left
+    let restore = move || {
+        if let Some(run) = session::from_url() {
+            session::clear_url();
+            seed.set_value(run.seed);
+            set_hyperparameters(run.hyperparameters);
+            open_dataset(run.source);
+            let locale = locale.get_untracked();
+            log!(
+                log_ref,
+                locale,
+                "已从分享链接载入, 随机种子 {}, {}, {}",
+                "Loaded from a shared link, seed {}, {}, {}",
+                run.seed,
+                display_method(&run.hyperparameters, locale),
+                display_hyperparameters(&run.hyperparameters, locale)
+            );
+            start_training();
+            return;
+        }
+        let Some(saved) = saved else {
+            return;
+        };
+        seed.set_value(saved.seed);
+        set_hyperparameters(saved.hyperparameters);
+        let Some(name) = saved.source else {
+            return;
+        };
+        open_dataset(name);
+        set_property(
+            &log_ref.get_untracked().unwrap().into_any(),
+            "value",
+            &Some(saved.log.into()),
+        );
+        show_axes(saved.x_axis, saved.y_axis);
+        if let Some(tree) = saved.tree {
+            set_rules(Some(extract_rules(
+                &tree,
+                &train_data.get_untracked().unwrap(),
+            )));
+            set_tree(Some(tree));
+            set_tree_hyperparameters(saved.tree_hyperparameters);
+        }
+    };
+    request_animation_frame(restore);
+    window_event_listener_untyped("pagehide", move |_| {
+        session::save(&Session {
+            locale: locale.get_untracked(),
+            source: source.get_untracked(),
+            seed: seed.get_value(),
+            x_axis: x_axis.get_untracked(),
+            y_axis: y_axis.get_untracked(),
+            hyperparameters: hyperparameters.get_untracked(),
+            tree: tree.get_untracked(),
+            tree_hyperparameters: tree_hyperparameters.get_untracked(),
+            log: get_property(&log_ref.get_untracked().unwrap().into_any(), "value")
+                .ok()
+                .and_then(|log| log.as_string())
+                .unwrap_or_default(),
+        });
+    });
+    let share = move |_| {
+        let Some(name) = source.get_untracked() else {
+            toast_alert(dataset_not_found_alert);
+            return;
+        };
+        // the tree shown is reproduced if there is one
+        let hyperparameters = match tree.get_untracked() {
+            Some(_) => tree_hyperparameters.get_untracked(),
+            None => hyperparameters.get_untracked(),
+        };
+        let url = session::to_url(&SharedRun {
+            source: name,
+            seed: seed.get_value(),
+            hyperparameters,
+        });
+        copy_text(&url);
+        log!(
+            log_ref,
+            locale.get_untracked(),
+            "分享链接已复制: {url}",
+            "Link copied: {url}"
+        );
+    };
+    let (class_name, style_val) = style_str! {
+        main {
+            flex: 1;
+            display: flex;
+            flex-direction: column;
+            width: 50%;
+            align-items: stretch;
+            gap: 1rem;
+            padding: 1rem;
+        }
+        h2 {
+            margin: 0;
+        }
+        .header {
+            display: flex;
+            flex-direction: row;
+            justify-content: space-between;
+            align-items: center;
+        }
+        .controls {
+            display: flex;
+            flex-direction: row;
+            align-items: end;
+            gap: 1rem;
+        }
+        .progress {
+            display: flex;
+            flex-direction: column;
+            gap: 0.25rem;
+        }
+        .anomalies {
+            display: flex;
+            flex-direction: column;
+            gap: 0.5rem;
+        }
+        .hint {
+            color: #71717a;
+            font-size: 0.875rem;
+        }
+        sl-textarea::part(textarea) {
+            white-space: pre-wrap;
+            overflow-wrap: break-word;
+            font-family: "DejaVu Sans Mono", ui-monospace, "Cascadia Code", Menlo,
+            "Source Code Pro", Consolas, monospace;
+        }
+    };
+    view! {
+        class = class_name,
+        <Style> { style_val } </Style>
+        <Html lang=move || locale().code() />
+        <main>
+            <div class="header">
+                <h2> {move || locale().pick("决策树", "Decision Tree")} </h2>
+                <sl-select size="small" prop:value=move || locale().code() on:sl-change=move |ev: JsValue| {
+                    let code = get(&get(&ev, "target"), "value").as_string().unwrap();
+                    set_locale(Locale::from_code(&code).unwrap_or_default());
+                }>
+                    {Locale::ALL
+                        .into_iter()
+                        .map(|locale| view! { <sl-option value=locale.code()> {locale.name()} </sl-option> })
+                        .collect_view()}
+                </sl-select>
+            </div>
+            <div class="controls">
+                <sl-select label=move || locale().pick("选择数据集", "Dataset") prop:value=move || source().unwrap_or_default() on:sl-change=move |ev: JsValue| {
+                    open_dataset(get(&get(&ev, "target"), "value").as_string().unwrap());
+                }>
+                    <sl-option value="iris"> {move || locale().pick("鸢尾花的尺寸", "Iris measurements")} </sl-option>
+                    <sl-option value="wine"> {move || locale().pick("红酒的理化性质", "Wine chemistry")} </sl-option>
+                    <sl-option value="rice"> {move || locale().pick("米粒的形状", "Rice grain shapes")} </sl-option>
+                </sl-select>
+                <sl-select label=move || locale().pick("X 轴数据点", "X axis") ref=x_ref on:sl-change=move |ev: JsValue| {
+                    match get(&get(&ev, "target"), "value").as_string().unwrap().parse().ok() {
+                        Some(x) => set_x_axis(Some(x)),
+                        None => set_x_axis(None),
+                    }
+                }>
+                </sl-select>
+                <sl-select label=move || locale().pick("Y 轴数据点", "Y axis") ref=y_ref on:sl-change=move |ev: JsValue| {
+                    match get(&get(&ev, "target"), "value").as_string().unwrap().parse().ok() {
+                        Some(y) => set_y_axis(Some(y)),
+                        None => set_y_axis(None),
+                    }
+                }>
+                </sl-select>
+                <sl-select label=move || locale().pick("算法", "Algorithm") prop:value=move || match hyperparameters().algorithm {
+                    Algorithm::Cart => "cart",
+                    Algorithm::Id3 => "id3",
+                    Algorithm::C45 => "c45",
+                    Algorithm::Conditional => "ctree",
+                } on:sl-change=move |ev: JsValue| {
+                    let algorithm = match get(&get(&ev, "target"), "value").as_string().unwrap().as_str() {
+                        "id3" => Algorithm::Id3,
+                        "c45" => Algorithm::C45,
+                        "ctree" => Algorithm::Conditional,
+                        _ => Algorithm::Cart,
+                    };
+                    set_hyperparameters.update(|hyperparameters| hyperparameters.algorithm = algorithm);
+                }>
+                    <sl-option value="cart"> "CART" </sl-option>
+                    <sl-option value="id3"> {move || locale().pick("ID3 (多路分裂, 信息增益)", "ID3 (multiway, information gain)")} </sl-option>
+                    <sl-option value="c45"> {move || locale().pick("C4.5 (增益率, 悲观剪枝)", "C4.5 (gain ratio, pessimistic pruning)")} </sl-option>
+                    <sl-option value="ctree"> {move || locale().pick("条件推断树 (χ² 检验)", "Conditional inference (χ² test)")} </sl-option>
+                </sl-select>
+                <sl-select label=move || locale().pick("分裂搜索", "Split search") disabled=move || hyperparameters().algorithm != Algorithm::Cart prop:value=move || match hyperparameters().split_search {
+                    SplitSearch::Exact => "exact",
+                    SplitSearch::Histogram { .. } => "histogram",
+                    SplitSearch::Oblique => "oblique",
+                } on:sl-change=move |ev: JsValue| {
+                    let split_search = match get(&get(&ev, "target"), "value").as_string().unwrap().as_str() {
+                        "histogram" => SplitSearch::Histogram { max_bins: MAX_BINS },
+                        "oblique" => SplitSearch::Oblique,
+                        _ => SplitSearch::Exact,
+                    };
+                    set_hyperparameters.update(|hyperparameters| hyperparameters.split_search = split_search);
+                }>
+                    <sl-option value="exact"> {move || locale().pick("精确", "Exact")} </sl-option>
+                    <sl-option value="histogram"> {move || locale().pick("直方图", "Histogram")} </sl-option>
+                    <sl-option value="oblique"> {move || locale().pick("斜分裂 (w·x < b)", "Oblique (w·x < b)")} </sl-option>
+                </sl-select>
+                <sl-button-group>
+                    <sl-button loading=training on:click=move |_| start_training()> {move || locale().pick("训练", "Train")} </sl-button>
+                    <sl-button disabled=move || !training() on:click=move |_| {
+                        if !training.get_untracked() {
+                            return;
+                        }
+                        worker.with_value(Worker::terminate);
+                        worker.set_value(spawn_worker(on_train_event));
+                        train_id.update_value(|id| *id += 1);
+                        set_training(false);
+                        set_progress(None);
+                        set_search_progress(None);
+                        log!(log_ref, locale.get_untracked(), "训练已取消", "Training cancelled");
+                    }> {move || locale().pick("取消", "Cancel")} </sl-button>
+                    <sl-button disabled=move || training() || !Growth::supports(&hyperparameters()) on:click=move |_| {
+                        if dataset.get_untracked().is_none() {
+                            toast_alert(dataset_not_found_alert);
+                            return;
+                        }
+                        stop();
+                        advance();
+                    }> {move || locale().pick("下一步", "Step")} </sl-button>
+                    <sl-button disabled=move || training() || !Growth::supports(&hyperparameters()) on:click=move |_| {
+                        if playing.get_untracked() {
+                            stop();
+                            return;
+                        }
+                        if dataset.get_untracked().is_none() {
+                            toast_alert(dataset_not_found_alert);
+                            return;
+                        }
+                        if !advance() {
+                            return;
+                        }
+                        let handle = set_interval_with_handle(
+                            move || {
+                                if !advance() {
+                                    stop();
+                                }
+                            },
+                            Duration::from_millis(800),
+                        )
+                        .unwrap();
+                        autoplay.set_value(Some(handle));
+                        set_playing(true);
+                    }> {move || if playing() { locale().pick("暂停", "Pause") } else { locale().pick("自动播放", "Autoplay") }} </sl-button>
+                    <sl-button on:click=move |_| {
+                        let begin = timestamp();
+                        if dataset().is_none() {
+                            toast_alert(dataset_not_found_alert);
+                            return;
+                        }
+                        let Some(tree) = tree() else {
+                            toast_alert(tree_not_trained_alert);
+                            return;
+                        };
+                        let predictions = collect_predictions(&tree, &classify_data().unwrap());
+                        let wrong = predictions.iter().filter(|p| p.truth != p.predicted).count();
+                        let correct_rate = 1. - wrong as f64 / predictions.len() as f64;
+                        let duration = (0.001f64).max(timestamp() - begin);
+                        log!(
+                            log_ref,
+                            locale.get_untracked(),
+                            "分类完毕, 测试集分类正确率 {:.3}%, 错分 {wrong} 个点, 用时 {duration:.3} 秒",
+                            "Classified, test accuracy {:.3}%, {wrong} points misclassified, took {duration:.3} s",
+                            correct_rate * 100.
+                        );
+                        set_predictions(Some(predictions));
+                        set_show_predictions(true);
+                    }> {move || locale().pick("分类", "Classify")} </sl-button>
+                </sl-button-group>
+                <Show when=move || !Growth::supports(&hyperparameters())>
+                    <span class="hint"> {move || locale().pick("逐步生长只支持 CART 精确搜索", "Stepping only supports CART with the exact split search")} </span>
+                </Show>
+                <sl-select label=move || locale().pick("导出代码", "Export code") ref=export_ref on:sl-change=move |ev: JsValue| {
+                    let language = match get(&get(&ev, "target"), "value").as_string().unwrap().as_str() {
+                        "rust" => Language::Rust,
+                        "python" => Language::Python,
+                        "c" => Language::C,
+                        "sql" => Language::Sql,
+                        _ => return,
+                    };
+                    set_property(&export_ref.get_untracked().unwrap().into_any(), "value", &Some("".into()));
+                    let Some(dataset) = dataset() else {
+                        toast_alert(dataset_not_found_alert);
+                        return;
+                    };
+                    let Some(tree) = tree() else {
+                        toast_alert(tree_not_trained_alert);
+                        return;
+                    };
+                    log!(
+                        log_ref,
+                        locale.get_untracked(),
+                        "导出代码:\n{}",
+                        "Exported code:\n{}",
+                        compile_tree(&dataset, &tree, language)
+                    );
+                }>
+                    <sl-option value="rust"> "Rust" </sl-option>
+                    <sl-option value="python"> "Python" </sl-option>
+                    <sl-option value="c"> "C" </sl-option>
+                    <sl-option value="sql"> "SQL CASE" </sl-option>
+                </sl-select>
+                <sl-alert variant="danger" duration="3000" closable ref=dataset_not_found_alert>
+                    <sl-icon slot="icon" name="exclamation-octagon"></sl-icon>
+                    {move || locale().pick("必须选择数据集", "Choose a dataset first")}
+                </sl-alert>
+                <sl-alert variant="danger" duration="3000" closable ref=bins_alert>
+                    <sl-icon slot="icon" name="exclamation-octagon"></sl-icon>
+                    {move || tr!(locale(), "直方图需要 2 到 {MAX_BINS} 个箱, 请重新选择分裂搜索", "A histogram needs 2 to {MAX_BINS} bins, choose the split search again")}
+                </sl-alert>
+                <sl-alert variant="danger" duration="3000" closable ref=x_y_same_alert>
+                    <sl-icon slot="icon" name="exclamation-octagon"></sl-icon>
+                    {move || locale().pick("X 轴和 Y 轴数据不能相同", "X and Y must be different axes")}
+                </sl-alert>
+                <sl-alert variant="danger" duration="3000" closable ref=tree_not_trained_alert>
+                    <sl-icon slot="icon" name="exclamation-octagon"></sl-icon>
+                    {move || locale().pick("必须先训练数据集", "Train a tree first")}
+                </sl-alert>
+            </div>
+            <div class="controls">
+                <sl-select label=move || locale().pick("超参数搜索", "Hyperparameter search") value="grid" on:sl-change=move |ev: JsValue| {
+                    set_random_search(get(&get(&ev, "target"), "value").as_string().unwrap() == "random");
+                }>
+                    <sl-option value="grid"> {move || locale().pick("网格搜索", "Grid search")} </sl-option>
+                    <sl-option value="random"> {move || tr!(locale(), "随机搜索 {RANDOM_SEARCH_SIZE} 组", "Random search, {RANDOM_SEARCH_SIZE} candidates")} </sl-option>
+                </sl-select>
+                <sl-button loading=training on:click=move |_| {
+                    if training.get_untracked() {
+                        return;
+                    }
+                    let Some(dataset) = dataset() else {
+                        toast_alert(dataset_not_found_alert);
+                        return;
+                    };
+                    if !hyperparameters.get_untracked().split_search.is_valid() {
+                        toast_alert(bins_alert);
+                        return;
+                    }
+                    reset_growth();
+                    train_begin.set_value(timestamp());
+                    train_id.update_value(|id| *id += 1);
+                    set_training(true);
+                    let base = hyperparameters.get_untracked();
+                    let grid = Grid::default();
+                    let candidates = if random_search.get_untracked() {
+                        grid.sample(&base, RANDOM_SEARCH_SIZE, seed.get_value())
+                    } else {
+                        grid.combinations(&base)
+                    };
+                    log!(
+                        log_ref,
+                        locale.get_untracked(),
+                        "开始搜索 {} 组超参数, 每组做 {SEARCH_FOLDS} 折交叉验证",
+                        "Searching {} candidates with {SEARCH_FOLDS}-fold cross-validation each",
+                        candidates.len()
+                    );
+                    let request = SearchRequest {
+                        id: train_id.get_value(),
+                        axises: dataset.mapped_axis.clone(),
+                        classes: dataset.mapped_class.clone(),
+                        data: train_data().unwrap(),
+                        candidates,
+                        folds: SEARCH_FOLDS,
+                        seed: seed.get_value(),
+                    };
+                    worker.with_value(|worker| post_search(worker, request));
+                }> {move || locale().pick("搜索", "Search")} </sl-button>
+                <sl-button on:click=share> {move || locale().pick("复制分享链接", "Copy share link")} </sl-button>
+            </div>
+            <Show when=training>
+                <div class="progress">
+                    <sl-progress-bar value=move || match search_progress() {
+                        Some((done, total)) => done as f64 / total.max(1) as f64 * 100.,
+                        None => progress().map_or(0., |p| p.settled as f64 / p.total.max(1) as f64 * 100.),
+                    }></sl-progress-bar>
+                    <span>
+                        {move || match (progress(), search_progress()) {
+                            (_, Some((done, total))) => tr!(locale(), "已评估 {done} / {total} 组超参数", "{done} / {total} candidates scored"),
+                            (Some(p), None) => tr!(locale(), "已生长 {} 个节点, 当前深度 {}", "{} nodes grown, depth {}", p.nodes, p.depth),
+                            (None, None) => locale().pick("等待训练开始", "Waiting for training to start").to_owned(),
+                        }}
+                    </span>
+                </div>
+            </Show>
+            <DatasetPanel dataset=dataset />
+            <Show when=move || predictions().is_some()>
+                <sl-switch prop:checked=show_predictions on:sl-change=move |ev: JsValue| {
+                    set_show_predictions(get(&get(&ev, "target"), "checked").as_bool().unwrap());
+                }> {move || locale().pick("显示测试集预测 (叉号为错分)", "Show test set predictions (crosses are misclassified)")} </sl-switch>
+            </Show>
+            <Show when=move || dataset().is_some()>
+                <div class="anomalies">
+                    <sl-switch prop:checked=show_anomalies on:sl-change=move |ev: JsValue| {
+                        set_show_anomalies(get(&get(&ev, "target"), "checked").as_bool().unwrap());
+                    }> {move || locale().pick("按孤立森林异常分数着色", "Shade points by isolation forest anomaly score")} </sl-switch>
+                    <Show when=show_anomalies>
+                        <sl-range label=move || locale().pick("异常阈值", "Outlier threshold") min="0.4" max="0.9" step="0.01" prop:value=anomaly_threshold on:sl-change=move |ev: JsValue| {
+                            set_anomaly_threshold(get(&get(&ev, "target"), "value").as_f64().unwrap());
+                        }></sl-range>
+                        <span>
+                            {move || {
+                                let flagged = anomalies().map_or(0, |scores| scores.iter().filter(|score| **score >= anomaly_threshold()).count());
+                                tr!(locale(), "{flagged} 个点分数不低于 {:.2}", "{flagged} points score at least {:.2}", anomaly_threshold())
+                            }}
+                        </span>
+                    </Show>
+                </div>
+            </Show>
+            <div class="chart">
+                <canvas ref=chart_ref />
+            </div>
+            <Overview dataset=dataset tree=tree x_axis=x_axis y_axis=y_axis on_select=Callback::new(select_pair) />
+            <StepPanel dataset=dataset hyperparameters=tree_hyperparameters step=step />
+            <ImpurityChart dataset=dataset train_data=train_data tree=tree hyperparameters=tree_hyperparameters step=step />
+            <AccuracyCurve dataset=dataset hyperparameters=hyperparameters seed=seed log_ref=log_ref />
+            <RankingCurves dataset=dataset hyperparameters=hyperparameters seed=seed log_ref=log_ref />
+            <SearchTable results=search_results />
+            <ModelRegistry models=models set_models=set_models test_data=classify_data log_ref=log_ref on_show=Callback::new(show_model) />
+            <RuleTable dataset=dataset rules=rules />
+            <sl-textarea label=move || locale().pick("日志", "Log") rows="15" ref=log_ref></sl-textarea>
+        </main>
+    }
+}
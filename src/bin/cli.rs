@@ -0,0 +1,678 @@
+use std::{
+    error::Error,
+    fs,
+    io::{self, Write},
+    num::NonZeroUsize,
+    path::PathBuf,
+};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use dectree::{
+    dataset::{split_rows, DataSet},
+    ensemble::ExtraTrees,
+    eval::{accuracy, confusion_matrix, k_fold_rows, mean_std, Classifier},
+    preprocess::{load_frame, load_labelled_frame, Frame, Pipeline, Transform},
+    search::{rank, Grid},
+    tree::{
+        train, Algorithm, Class, DataPoint, DecisionTree, Hyperparameters, Impurity, LabelledData,
+        SplitSearch,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Parser)]
+#[command(version, about = "Train and evaluate CART decision trees on CSV files")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Train a tree on a CSV file whose last column is the class
+    Train {
+        #[arg(long)]
+        data: PathBuf,
+        /// Where to write the trained model (JSON)
+        #[arg(long)]
+        model: PathBuf,
+        #[command(flatten)]
+        hyperparameters: HyperparameterArgs,
+        #[command(flatten)]
+        preprocess: PreprocessArgs,
+        /// Fit an ensemble of this many extremely randomized trees instead of one tree
+        #[arg(long)]
+        extra_trees: Option<NonZeroUsize>,
+        /// Fraction of rows held out to report test accuracy
+        #[arg(long, default_value_t = 0.)]
+        test_ratio: f64,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Where to write metrics (JSON)
+        #[arg(long)]
+        metrics: Option<PathBuf>,
+    },
+    /// Predict the class of every row in a CSV file
+    Predict {
+        #[arg(long)]
+        model: PathBuf,
+        #[arg(long)]
+        data: PathBuf,
+        /// Where to write the rows with an appended prediction column, stdout if omitted
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Evaluate a model on a CSV file whose last column is the class
+    Eval {
+        #[arg(long)]
+        model: PathBuf,
+        #[arg(long)]
+        data: PathBuf,
+        #[arg(long)]
+        metrics: Option<PathBuf>,
+    },
+    /// K-fold cross-validation on a CSV file whose last column is the class
+    Cv {
+        #[arg(long)]
+        data: PathBuf,
+        #[command(flatten)]
+        hyperparameters: HyperparameterArgs,
+        #[command(flatten)]
+        preprocess: PreprocessArgs,
+        /// Cross-validate an ensemble of this many extremely randomized trees
+        #[arg(long)]
+        extra_trees: Option<NonZeroUsize>,
+        #[arg(long, default_value_t = 5)]
+        folds: usize,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        #[arg(long)]
+        metrics: Option<PathBuf>,
+    },
+    /// Rank hyperparameter combinations by k-fold cross-validation and refit the best one
+    Search {
+        #[arg(long)]
+        data: PathBuf,
+        #[command(flatten)]
+        preprocess: PreprocessArgs,
+        #[arg(long, value_delimiter = ',', default_values_t = Grid::default().max_depth)]
+        max_depth: Vec<usize>,
+        #[arg(long, value_delimiter = ',', default_values_t = Grid::default().min_elements_per_leaf)]
+        min_elements_per_leaf: Vec<usize>,
+        #[arg(long, value_delimiter = ',', value_enum, default_values = ["gini", "entropy"])]
+        impurity: Vec<ImpurityArg>,
+        #[arg(long, value_delimiter = ',', default_values_t = Grid::default().ccp_alpha)]
+        ccp_alpha: Vec<f64>,
+        /// Score this many random combinations instead of the whole grid
+        #[arg(long)]
+        random: Option<usize>,
+        #[arg(long, default_value_t = 5)]
+        folds: usize,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Where to write the best model refitted on all rows (JSON)
+        #[arg(long)]
+        model: Option<PathBuf>,
+        /// Where to write every result, best first (JSON)
+        #[arg(long)]
+        metrics: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ImpurityArg {
+    Gini,
+    Entropy,
+}
+
+impl From<ImpurityArg> for Impurity {
+    fn from(value: ImpurityArg) -> Self {
+        match value {
+            ImpurityArg::Gini => Impurity::Gini,
+            ImpurityArg::Entropy => Impurity::Entropy,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum AlgorithmArg {
+    Cart,
+    Id3,
+    C45,
+    Ctree,
+}
+
+impl From<AlgorithmArg> for Algorithm {
+    fn from(value: AlgorithmArg) -> Self {
+        match value {
+            AlgorithmArg::Cart => Algorithm::Cart,
+            AlgorithmArg::Id3 => Algorithm::Id3,
+            AlgorithmArg::C45 => Algorithm::C45,
+            AlgorithmArg::Ctree => Algorithm::Conditional,
+        }
+    }
+}
+
+#[derive(Args)]
+struct HyperparameterArgs {
+    /// ID3 grows multiway splits by information gain, C4.5 binary splits by gain ratio
+    /// with pessimistic-error pruning, ctree picks the axis by a χ² test and stops when
+    /// no test is significant; the split search and ccp-alpha only apply to CART
+    #[arg(long, value_enum, default_value = "cart")]
+    algorithm: AlgorithmArg,
+    /// Šidák adjusted p-value a split needs with --algorithm ctree
+    #[arg(long, default_value_t = Hyperparameters::default().significance)]
+    significance: f64,
+    #[arg(long, default_value_t = Hyperparameters::default().max_depth)]
+    max_depth: usize,
+    #[arg(long, default_value_t = Hyperparameters::default().min_elements_to_split)]
+    min_elements_to_split: usize,
+    /// Find splits from quantile histograms with at most this many bins (up to 256) instead
+    /// of the exact search
+    #[arg(long, value_parser = parse_bins)]
+    histogram_bins: Option<usize>,
+    /// Also try splits on a linear combination of the features (w·x < b) at every node
+    #[arg(long, conflicts_with = "histogram_bins")]
+    oblique: bool,
+    #[arg(long, default_value_t = Hyperparameters::default().min_elements_per_leaf)]
+    min_elements_per_leaf: usize,
+    #[arg(long, value_enum, default_value = "gini")]
+    impurity: ImpurityArg,
+    /// Cost-complexity pruning strength, 0 disables pruning
+    #[arg(long, default_value_t = Hyperparameters::default().ccp_alpha)]
+    ccp_alpha: f64,
+}
+
+impl From<HyperparameterArgs> for Hyperparameters {
+    fn from(value: HyperparameterArgs) -> Self {
+        Hyperparameters {
+            max_depth: value.max_depth,
+            min_elements_to_split: value.min_elements_to_split,
+            split_search: match value.histogram_bins {
+                Some(max_bins) => SplitSearch::Histogram { max_bins },
+                None if value.oblique => SplitSearch::Oblique,
+                None => SplitSearch::Exact,
+            },
+            min_elements_per_leaf: value.min_elements_per_leaf,
+            impurity: value.impurity.into(),
+            ccp_alpha: value.ccp_alpha,
+            algorithm: value.algorithm.into(),
+            significance: value.significance,
+        }
+    }
+}
+
+// applied in this order: drop, one-hot, ordinal, log, Box-Cox, ratio, product,
+// standardize
+#[derive(Args)]
+struct PreprocessArgs {
+    /// Columns to leave out
+    #[arg(long, value_delimiter = ',')]
+    drop: Vec<String>,
+    /// Categorical columns to replace with one 0/1 column per category
+    #[arg(long, value_delimiter = ',')]
+    one_hot: Vec<String>,
+    /// Categorical columns to number in order of first appearance
+    #[arg(long, value_delimiter = ',')]
+    ordinal: Vec<String>,
+    /// Columns to replace with ln(1 + x)
+    #[arg(long, value_delimiter = ',')]
+    log: Vec<String>,
+    /// Positive columns to Box-Cox transform, λ is fitted by maximum likelihood
+    #[arg(long, value_delimiter = ',')]
+    box_cox: Vec<String>,
+    /// Add a column "a/b" for every pair a:b
+    #[arg(long, value_delimiter = ',', value_parser = parse_pair)]
+    ratio: Vec<(String, String)>,
+    /// Add a column "a*b" for every pair a:b
+    #[arg(long, value_delimiter = ',', value_parser = parse_pair)]
+    product: Vec<(String, String)>,
+    /// Columns to scale to zero mean and unit variance
+    #[arg(long, value_delimiter = ',')]
+    standardize: Vec<String>,
+}
+
+fn parse_bins(value: &str) -> Result<usize, String> {
+    let max_bins = value.parse().map_err(|e| format!("{e}"))?;
+    let split_search = SplitSearch::Histogram { max_bins };
+    if split_search.is_valid() {
+        Ok(max_bins)
+    } else {
+        Err(format!("expected between 2 and 256 bins, got {max_bins}"))
+    }
+}
+
+fn parse_pair(value: &str) -> Result<(String, String), String> {
+    value
+        .split_once(':')
+        .map(|(a, b)| (a.to_owned(), b.to_owned()))
+        .ok_or_else(|| format!("expected two columns as a:b, got {value:?}"))
+}
+
+impl PreprocessArgs {
+    fn transforms(self) -> Vec<Transform> {
+        let mut transforms = Vec::new();
+        if !self.drop.is_empty() {
+            transforms.push(Transform::Drop(self.drop));
+        }
+        transforms.extend(self.one_hot.into_iter().map(Transform::OneHot));
+        transforms.extend(self.ordinal.into_iter().map(|column| Transform::Ordinal {
+            column,
+            categories: None,
+        }));
+        transforms.extend(self.log.into_iter().map(Transform::Log));
+        transforms.extend(self.box_cox.into_iter().map(|column| Transform::BoxCox {
+            column,
+            lambda: None,
+        }));
+        transforms.extend(self.ratio.into_iter().map(|(a, b)| Transform::Ratio(a, b)));
+        transforms.extend(
+            self.product
+                .into_iter()
+                .map(|(a, b)| Transform::Product(a, b)),
+        );
+        transforms.extend(self.standardize.into_iter().map(Transform::Standardize));
+        transforms
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Model {
+    // the features after preprocessing
+    axises: Vec<String>,
+    classes: Vec<String>,
+    #[serde(default)]
+    preprocessing: Pipeline,
+    hyperparameters: Hyperparameters,
+    #[serde(flatten)]
+    trees: Trees,
+}
+
+// stored under "tree" or "extra_trees"
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Trees {
+    Tree(DecisionTree),
+    ExtraTrees(ExtraTrees),
+}
+
+impl Trees {
+    fn classifier(&self) -> &dyn Classifier {
+        match self {
+            Trees::Tree(tree) => tree,
+            Trees::ExtraTrees(forest) => forest,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Metrics {
+    accuracy: f64,
+    classes: Vec<String>,
+    confusion_matrix: Vec<Vec<usize>>,
+}
+
+#[derive(Serialize)]
+struct CvMetrics {
+    folds: Vec<f64>,
+    mean: f64,
+    std: f64,
+}
+
+// (dataset, train rows, test rows) of every fold
+type Folds = Vec<(DataSet, LabelledData, LabelledData)>;
+
+// a labelled CSV file before preprocessing
+struct Source {
+    frame: Frame,
+    labels: Vec<String>,
+    transforms: Vec<Transform>,
+}
+
+fn read_source(path: &PathBuf, preprocess: PreprocessArgs) -> Result<Source, Box<dyn Error>> {
+    let (frame, labels) = load_labelled_frame(&fs::read_to_string(path)?)?;
+    Ok(Source {
+        frame,
+        labels,
+        transforms: preprocess.transforms(),
+    })
+}
+
+impl Source {
+    fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    // the pipeline is fitted on the `train` rows only and every row is passed
+    // through it, so held-out rows do not inform the preprocessing; classes
+    // are numbered over the whole file
+    fn preprocess(&self, train: &[usize]) -> Result<(DataSet, Pipeline), Box<dyn Error>> {
+        let (pipeline, _frame) = Pipeline::fit(&self.transforms, self.frame.select(train))?;
+        let dataset = pipeline
+            .apply(self.frame.clone())?
+            .into_dataset(self.labels.clone())?;
+        Ok((dataset, pipeline))
+    }
+
+    // every fold preprocessed with a pipeline fitted on its own train rows
+    fn folds(&self, k: usize, seed: u64) -> Result<Folds, Box<dyn Error>> {
+        k_fold_rows(self.len(), k, seed)
+            .into_iter()
+            .map(|(train_rows, test_rows)| {
+                let (dataset, _pipeline) = self.preprocess(&train_rows)?;
+                let labelled = dataset.labelled();
+                let train_data = pick(&labelled, &train_rows);
+                let test_data = pick(&labelled, &test_rows);
+                Ok((dataset, train_data, test_data))
+            })
+            .collect()
+    }
+}
+
+// every fold needs a row to test on
+fn check_folds(folds: usize, rows: usize) -> Result<(), Box<dyn Error>> {
+    if folds < 2 {
+        return Err("need at least 2 folds".into());
+    }
+    if folds > rows {
+        return Err(format!("{folds} folds need at least {folds} rows, got {rows}").into());
+    }
+    Ok(())
+}
+
+fn pick(data: &[(DataPoint, Class)], rows: &[usize]) -> LabelledData {
+    rows.iter().map(|i| data[*i].clone()).collect()
+}
+
+fn read_model(path: &PathBuf) -> Result<Model, Box<dyn Error>> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn write_json(path: &Option<PathBuf>, value: &impl Serialize) -> Result<(), Box<dyn Error>> {
+    if let Some(path) = path {
+        fs::write(path, serde_json::to_string_pretty(value)?)?;
+    }
+    Ok(())
+}
+
+type Records = Vec<(DataPoint, csv::StringRecord)>;
+
+// columns are matched to the model by header name after the model's
+// preprocessing, so the CSV may reorder or add columns; returns the feature
+// vectors and the records they came from
+fn read_features(
+    model: &Model,
+    path: &PathBuf,
+) -> Result<(csv::StringRecord, Records), Box<dyn Error>> {
+    let csv = fs::read_to_string(path)?;
+    let points = model
+        .preprocessing
+        .apply(load_frame(&csv)?)?
+        .points(&model.axises)?;
+    let mut rdr = csv::Reader::from_reader(csv.as_bytes());
+    let headers = rdr.headers()?.clone();
+    let records = rdr.records().collect::<Result<Vec<_>, _>>()?;
+    Ok((headers, points.into_iter().zip(records).collect()))
+}
+
+fn metrics(model: &Model, data: &[(DataPoint, Class)]) -> Metrics {
+    let classes: Vec<Class> = (0..model.classes.len() as i32).map(Class).collect();
+    Metrics {
+        accuracy: accuracy(model.trees.classifier(), data),
+        classes: model.classes.clone(),
+        confusion_matrix: confusion_matrix(model.trees.classifier(), &classes, data),
+    }
+}
+
+fn run(command: Command) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Train {
+            data,
+            model,
+            hyperparameters,
+            preprocess,
+            extra_trees,
+            test_ratio,
+            seed,
+            metrics: metrics_path,
+        } => {
+            if !(0. ..1.).contains(&test_ratio) {
+                return Err("--test-ratio must be at least 0 and below 1".into());
+            }
+            let source = read_source(&data, preprocess)?;
+            let hyperparameters = Hyperparameters::from(hyperparameters);
+            let test_len = (source.len() as f64 * test_ratio).round() as usize;
+            let (train_rows, test_rows) = split_rows(source.len(), test_len, seed);
+            if train_rows.is_empty() {
+                return Err("no rows left to train on".into());
+            }
+            let (dataset, preprocessing) = source.preprocess(&train_rows)?;
+            let labelled = dataset.labelled();
+            let train_data = pick(&labelled, &train_rows);
+            let test_data = pick(&labelled, &test_rows);
+            let trees = match extra_trees {
+                Some(trees) => Trees::ExtraTrees(ExtraTrees::fit(
+                    &dataset.mapped_axis,
+                    &dataset.mapped_class,
+                    &train_data,
+                    &hyperparameters,
+                    trees.get(),
+                    seed,
+                )),
+                None => Trees::Tree(train(
+                    &dataset.mapped_axis,
+                    &dataset.mapped_class,
+                    train_data.clone(),
+                    &hyperparameters,
+                )),
+            };
+            let trained = Model {
+                axises: dataset.axises.clone(),
+                classes: dataset.classes.clone(),
+                preprocessing,
+                hyperparameters,
+                trees,
+            };
+            println!(
+                "train accuracy {:.3}%",
+                accuracy(trained.trees.classifier(), &train_data) * 100.
+            );
+            let result = if test_data.is_empty() {
+                metrics(&trained, &train_data)
+            } else {
+                metrics(&trained, &test_data)
+            };
+            if !test_data.is_empty() {
+                println!("test accuracy {:.3}%", result.accuracy * 100.);
+            }
+            fs::write(&model, serde_json::to_string_pretty(&trained)?)?;
+            write_json(&metrics_path, &result)?;
+        }
+        Command::Predict {
+            model,
+            data,
+            output,
+        } => {
+            let model = read_model(&model)?;
+            let (mut headers, rows) = read_features(&model, &data)?;
+            headers.push_field("prediction");
+            let out: Box<dyn Write> = match output {
+                Some(path) => Box::new(fs::File::create(path)?),
+                None => Box::new(io::stdout()),
+            };
+            let mut wtr = csv::Writer::from_writer(out);
+            wtr.write_record(&headers)?;
+            for (dp, mut record) in rows {
+                let class = model.trees.classifier().predict(&dp);
+                record.push_field(&model.classes[class.0 as usize]);
+                wtr.write_record(&record)?;
+            }
+            wtr.flush()?;
+        }
+        Command::Eval {
+            model,
+            data,
+            metrics: metrics_path,
+        } => {
+            let model = read_model(&model)?;
+            let (_headers, rows) = read_features(&model, &data)?;
+            let labelled = rows
+                .into_iter()
+                .map(|(dp, record)| {
+                    let label = record.iter().next_back().unwrap_or_default();
+                    let class = model
+                        .classes
+                        .iter()
+                        .position(|class| class == label)
+                        .ok_or_else(|| format!("unknown class {label:?}"))?;
+                    Ok((dp, Class(class as i32)))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            let result = metrics(&model, &labelled);
+            println!("accuracy {:.3}%", result.accuracy * 100.);
+            for (class, row) in result.classes.iter().zip(result.confusion_matrix.iter()) {
+                println!("{class}\t{row:?}");
+            }
+            write_json(&metrics_path, &result)?;
+        }
+        Command::Cv {
+            data,
+            hyperparameters,
+            preprocess,
+            extra_trees,
+            folds,
+            seed,
+            metrics: metrics_path,
+        } => {
+            let source = read_source(&data, preprocess)?;
+            check_folds(folds, source.len())?;
+            let hyperparameters = Hyperparameters::from(hyperparameters);
+            let scores: Vec<f64> = source
+                .folds(folds, seed)?
+                .into_iter()
+                .map(|(dataset, train_data, test_data)| match extra_trees {
+                    Some(trees) => {
+                        let forest = ExtraTrees::fit(
+                            &dataset.mapped_axis,
+                            &dataset.mapped_class,
+                            &train_data,
+                            &hyperparameters,
+                            trees.get(),
+                            seed,
+                        );
+                        accuracy(&forest, &test_data)
+                    }
+                    None => {
+                        let tree = train(
+                            &dataset.mapped_axis,
+                            &dataset.mapped_class,
+                            train_data,
+                            &hyperparameters,
+                        );
+                        accuracy(&tree, &test_data)
+                    }
+                })
+                .collect();
+            let (mean, std) = mean_std(&scores);
+            for (i, score) in scores.iter().enumerate() {
+                println!("fold {} accuracy {:.3}%", i + 1, score * 100.);
+            }
+            println!("mean {:.3}% std {:.3}%", mean * 100., std * 100.);
+            write_json(
+                &metrics_path,
+                &CvMetrics {
+                    folds: scores,
+                    mean,
+                    std,
+                },
+            )?;
+        }
+        Command::Search {
+            data,
+            preprocess,
+            max_depth,
+            min_elements_per_leaf,
+            impurity,
+            ccp_alpha,
+            random,
+            folds,
+            seed,
+            model,
+            metrics: metrics_path,
+        } => {
+            let source = read_source(&data, preprocess)?;
+            check_folds(folds, source.len())?;
+            let grid = Grid {
+                max_depth,
+                min_elements_per_leaf,
+                impurity: impurity.into_iter().map(Impurity::from).collect(),
+                ccp_alpha,
+            };
+            let base = Hyperparameters::default();
+            let candidates = match random {
+                Some(n) => grid.sample(&base, n, seed),
+                None => grid.combinations(&base),
+            };
+            if candidates.is_empty() {
+                return Err("no hyperparameters to search".into());
+            }
+            let folds = source.folds(folds, seed)?;
+            let results = rank(&candidates, |hyperparameters| {
+                folds
+                    .iter()
+                    .map(|(dataset, train_data, test_data)| {
+                        let tree = train(
+                            &dataset.mapped_axis,
+                            &dataset.mapped_class,
+                            train_data.clone(),
+                            hyperparameters,
+                        );
+                        accuracy(&tree, test_data)
+                    })
+                    .collect()
+            });
+            println!("rank\tmax_depth\tmin_per_leaf\timpurity\tccp_alpha\tmean\tstd");
+            for (rank, result) in results.iter().enumerate().take(10) {
+                let h = &result.hyperparameters;
+                println!(
+                    "{}\t{}\t{}\t{:?}\t{}\t{:.3}%\t{:.3}%",
+                    rank + 1,
+                    h.max_depth,
+                    h.min_elements_per_leaf,
+                    h.impurity,
+                    h.ccp_alpha,
+                    result.mean * 100.,
+                    result.std * 100.
+                );
+            }
+            if let Some(model) = model {
+                let all_rows: Vec<usize> = (0..source.len()).collect();
+                let (dataset, preprocessing) = source.preprocess(&all_rows)?;
+                let hyperparameters = results[0].hyperparameters;
+                let tree = train(
+                    &dataset.mapped_axis,
+                    &dataset.mapped_class,
+                    dataset.labelled(),
+                    &hyperparameters,
+                );
+                let best = Model {
+                    axises: dataset.axises.clone(),
+                    classes: dataset.classes.clone(),
+                    preprocessing,
+                    hyperparameters,
+                    trees: Trees::Tree(tree),
+                };
+                fs::write(model, serde_json::to_string_pretty(&best)?)?;
+            }
+            write_json(&metrics_path, &results)?;
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run(Cli::parse().command) {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+}
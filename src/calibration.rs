@@ -0,0 +1,418 @@
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::tree::{leaves, train, Axis, Class, DataPoint, DecisionTree, Hyperparameters};
+
+// the depth-first number of the leaf `dp` falls in
+fn leaf(tree: &DecisionTree, dp: &DataPoint) -> usize {
+    match tree {
+        DecisionTree::Branch(c, left, right) => {
+            if dp[c.axis] < c.split {
+                leaf(left, dp)
+            } else {
+                leaves(left) + leaf(right, dp)
+            }
+        }
+        DecisionTree::Oblique(hyperplane, left, right) => {
+            if hyperplane.value(dp) < hyperplane.split {
+                leaf(left, dp)
+            } else {
+                leaves(left) + leaf(right, dp)
+            }
+        }
+        DecisionTree::Multiway(multiway, subtrees) => {
+            let child = multiway.child(dp[multiway.axis]);
+            subtrees[..child].iter().map(leaves).sum::<usize>() + leaf(&subtrees[child], dp)
+        }
+        DecisionTree::Leave(_) => 0,
+    }
+}
+
+// class probabilities of a tree: the frequencies of the classes among the
+// training rows reaching each leaf, add-one smoothed so none is 0
+#[derive(Debug, Clone)]
+pub struct LeafProbabilities {
+    tree: DecisionTree,
+    leaves: Vec<Vec<f64>>,
+}
+
+impl LeafProbabilities {
+    pub fn fit(tree: &DecisionTree, classes: &[Class], data: &[(DataPoint, Class)]) -> Self {
+        let mut counts = vec![vec![1.; classes.len()]; leaves(tree)];
+        for (dp, class) in data {
+            if let Some(i) = classes.iter().position(|c| c == class) {
+                counts[leaf(tree, dp)][i] += 1.;
+            }
+        }
+        for counts in counts.iter_mut() {
+            let total: f64 = counts.iter().sum();
+            counts.iter_mut().for_each(|count| *count /= total);
+        }
+        LeafProbabilities {
+            tree: tree.clone(),
+            leaves: counts,
+        }
+    }
+
+    // by position in `classes`
+    pub fn probabilities(&self, dp: &DataPoint) -> Vec<f64> {
+        self.leaves[leaf(&self.tree, dp)].clone()
+    }
+}
+
+// the leaf probabilities of every row of `data` from a tree that did not see
+// it, trained on the other folds
+pub fn out_of_fold_probabilities(
+    axises: &[Axis],
+    classes: &[Class],
+    data: &[(DataPoint, Class)],
+    k: usize,
+    seed: u64,
+    hyperparameters: &Hyperparameters,
+) -> Vec<Vec<f64>> {
+    let mut rng = ChaCha12Rng::seed_from_u64(seed);
+    let mut index: Vec<usize> = (0..data.len()).collect();
+    index.shuffle(&mut rng);
+    let mut probabilities = vec![Vec::new(); data.len()];
+    for fold in 0..k {
+        let mut test = Vec::new();
+        let mut train_data = Vec::new();
+        for (position, i) in index.iter().enumerate() {
+            if position % k == fold {
+                test.push(*i);
+            } else {
+                train_data.push(data[*i].clone());
+            }
+        }
+        let tree = train(axises, classes, train_data.clone(), hyperparameters);
+        let leaves = LeafProbabilities::fit(&tree, classes, &train_data);
+        for i in test {
+            probabilities[i] = leaves.probabilities(&data[i].0);
+        }
+    }
+    probabilities
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Calibration {
+    #[default]
+    None,
+    // a sigmoid of the score fitted by maximum likelihood
+    Platt,
+    // the best non-decreasing step function of the score, pool adjacent violators
+    Isotonic,
+}
+
+#[derive(Debug, Clone)]
+enum Curve {
+    Identity,
+    // 1 / (1 + exp(a s + b))
+    Sigmoid(f64, f64),
+    // (lowest score of the block, calibrated probability), by increasing score
+    Steps(Vec<(f64, f64)>),
+}
+
+impl Curve {
+    fn apply(&self, score: f64) -> f64 {
+        match self {
+            Curve::Identity => score,
+            Curve::Sigmoid(a, b) => 1. / (1. + (a * score + b).exp()),
+            Curve::Steps(steps) => {
+                let i = steps.partition_point(|(lower, _)| *lower <= score);
+                steps[i.saturating_sub(1)].1
+            }
+        }
+    }
+}
+
+// Newton's method on the log loss with Platt's smoothed targets, halving the
+// step until the loss decreases
+fn fit_sigmoid(scores: &[f64], positives: &[bool]) -> Curve {
+    let len_positive = positives.iter().filter(|p| **p).count() as f64;
+    let len_negative = positives.len() as f64 - len_positive;
+    let targets: Vec<f64> = positives
+        .iter()
+        .map(|p| match p {
+            true => (len_positive + 1.) / (len_positive + 2.),
+            false => 1. / (len_negative + 2.),
+        })
+        .collect();
+    let loss = |a: f64, b: f64| -> f64 {
+        scores
+            .iter()
+            .zip(&targets)
+            .map(|(s, t)| {
+                let z = a * s + b;
+                // log(1 + e^z) - (1 - t) z, computed without overflow
+                z.max(0.) + (-z.abs()).exp().ln_1p() - (1. - t) * z
+            })
+            .sum()
+    };
+    let (mut a, mut b) = (0., ((len_negative + 1.) / (len_positive + 1.)).ln());
+    let mut current = loss(a, b);
+    for _ in 0..100 {
+        let (mut ga, mut gb, mut haa, mut hab, mut hbb) = (0., 0., 1e-12, 0., 1e-12);
+        for (s, t) in scores.iter().zip(&targets) {
+            let p = 1. / (1. + (a * s + b).exp());
+            let d = t - p;
+            let w = p * (1. - p);
+            ga += s * d;
+            gb += d;
+            haa += s * s * w;
+            hab += s * w;
+            hbb += w;
+        }
+        let det = haa * hbb - hab * hab;
+        let (da, db) = (-(hbb * ga - hab * gb) / det, -(-hab * ga + haa * gb) / det);
+        let mut step = 1.;
+        while step > 1e-10 {
+            let next = loss(a + step * da, b + step * db);
+            if next < current {
+                a += step * da;
+                b += step * db;
+                break;
+            }
+            step /= 2.;
+        }
+        if step <= 1e-10 || (da.abs() + db.abs()) * step < 1e-10 {
+            break;
+        }
+        current = loss(a, b);
+    }
+    Curve::Sigmoid(a, b)
+}
+
+fn fit_isotonic(scores: &[f64], positives: &[bool]) -> Curve {
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    order.sort_by(|i, j| scores[*i].total_cmp(&scores[*j]));
+    // (lowest score, sum of targets, weight)
+    let mut blocks: Vec<(f64, f64, f64)> = Vec::new();
+    for i in order {
+        let target = if positives[i] { 1. } else { 0. };
+        match blocks.last_mut() {
+            // equal scores must get the same probability
+            Some(last) if last.0 == scores[i] => {
+                last.1 += target;
+                last.2 += 1.;
+            }
+            _ => blocks.push((scores[i], target, 1.)),
+        }
+        while blocks.len() >= 2 {
+            let (last, previous) = (blocks[blocks.len() - 1], blocks[blocks.len() - 2]);
+            if previous.1 / previous.2 <= last.1 / last.2 {
+                break;
+            }
+            blocks.pop();
+            let merged = blocks.last_mut().unwrap();
+            merged.1 += last.1;
+            merged.2 += last.2;
+        }
+    }
+    Curve::Steps(
+        blocks
+            .into_iter()
+            .map(|(lower, sum, weight)| (lower, sum / weight))
+            .collect(),
+    )
+}
+
+// one-vs-rest calibration of each class's probability, renormalized to sum to 1
+#[derive(Debug, Clone)]
+pub struct Calibrator {
+    curves: Vec<Curve>,
+}
+
+impl Calibrator {
+    // `labels` are positions in the classes the probabilities are indexed by
+    pub fn fit(method: Calibration, probabilities: &[Vec<f64>], labels: &[usize]) -> Calibrator {
+        let classes = probabilities.first().map_or(0, Vec::len);
+        let curves = (0..classes)
+            .map(|class| {
+                let scores: Vec<f64> = probabilities.iter().map(|p| p[class]).collect();
+                let positives: Vec<bool> = labels.iter().map(|label| *label == class).collect();
+                match method {
+                    Calibration::None => Curve::Identity,
+                    Calibration::Platt => fit_sigmoid(&scores, &positives),
+                    Calibration::Isotonic => fit_isotonic(&scores, &positives),
+                }
+            })
+            .collect();
+        Calibrator { curves }
+    }
+
+    pub fn apply(&self, probabilities: &[f64]) -> Vec<f64> {
+        let calibrated: Vec<f64> = self
+            .curves
+            .iter()
+            .zip(probabilities)
+            .map(|(curve, p)| curve.apply(*p))
+            .collect();
+        let total: f64 = calibrated.iter().sum();
+        if total > 0. {
+            calibrated.iter().map(|p| p / total).collect()
+        } else {
+            vec![1. / calibrated.len() as f64; calibrated.len()]
+        }
+    }
+}
+
+// (true positives, false positives) after each group of equal scores, from the
+// highest score down
+fn cumulative_counts(scores: &[f64], positives: &[bool]) -> Vec<(usize, usize)> {
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    order.sort_by(|i, j| scores[*j].total_cmp(&scores[*i]));
+    let mut counts = Vec::new();
+    let (mut tp, mut fp) = (0, 0);
+    for (position, i) in order.iter().enumerate() {
+        if positives[*i] {
+            tp += 1;
+        } else {
+            fp += 1;
+        }
+        if order
+            .get(position + 1)
+            .is_none_or(|next| scores[*next] != scores[*i])
+        {
+            counts.push((tp, fp));
+        }
+    }
+    counts
+}
+
+// (false positive rate, true positive rate) from (0, 0) to (1, 1), empty
+// unless there are both positives and negatives
+pub fn roc_curve(scores: &[f64], positives: &[bool]) -> Vec<(f64, f64)> {
+    let len_positive = positives.iter().filter(|p| **p).count();
+    let len_negative = positives.len() - len_positive;
+    if len_positive == 0 || len_negative == 0 {
+        return Vec::new();
+    }
+    [(0., 0.)]
+        .into_iter()
+        .chain(
+            cumulative_counts(scores, positives)
+                .into_iter()
+                .map(|(tp, fp)| {
+                    (
+                        fp as f64 / len_negative as f64,
+                        tp as f64 / len_positive as f64,
+                    )
+                }),
+        )
+        .collect()
+}
+
+// the area under a curve of (x, y) points with increasing x, by the trapezoidal rule
+pub fn auc(curve: &[(f64, f64)]) -> f64 {
+    curve
+        .windows(2)
+        .map(|w| (w[1].0 - w[0].0) * (w[0].1 + w[1].1) / 2.)
+        .sum()
+}
+
+// (recall, precision) from recall 0 at precision 1, empty without positives
+pub fn pr_curve(scores: &[f64], positives: &[bool]) -> Vec<(f64, f64)> {
+    let len_positive = positives.iter().filter(|p| **p).count();
+    if len_positive == 0 {
+        return Vec::new();
+    }
+    [(0., 1.)]
+        .into_iter()
+        .chain(
+            cumulative_counts(scores, positives)
+                .into_iter()
+                .map(|(tp, fp)| {
+                    (
+                        tp as f64 / len_positive as f64,
+                        tp as f64 / (tp + fp) as f64,
+                    )
+                }),
+        )
+        .collect()
+}
+
+// Σ (R_n - R_{n-1}) P_n over a precision-recall curve
+pub fn average_precision(curve: &[(f64, f64)]) -> f64 {
+    curve.windows(2).map(|w| (w[1].0 - w[0].0) * w[1].1).sum()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReliabilityBin {
+    // the mean probability of the predicted class
+    pub confidence: f64,
+    // how often the predicted class is right
+    pub accuracy: f64,
+    pub len: usize,
+}
+
+// the predictions grouped by the probability of the predicted class into
+// `bins` equal-width bins, empty bins are left out
+pub fn reliability(
+    probabilities: &[Vec<f64>],
+    labels: &[usize],
+    bins: usize,
+) -> Vec<ReliabilityBin> {
+    let mut sums = vec![(0., 0usize, 0usize); bins];
+    for (p, label) in probabilities.iter().zip(labels) {
+        let (predicted, confidence) =
+            p.iter().enumerate().fold(
+                (0, f64::MIN),
+                |best, (i, p)| if *p > best.1 { (i, *p) } else { best },
+            );
+        let bin = ((confidence * bins as f64) as usize).min(bins - 1);
+        sums[bin].0 += confidence;
+        sums[bin].1 += usize::from(predicted == *label);
+        sums[bin].2 += 1;
+    }
+    sums.into_iter()
+        .filter(|(_, _, len)| *len > 0)
+        .map(|(confidence, correct, len)| ReliabilityBin {
+            confidence: confidence / len as f64,
+            accuracy: correct as f64 / len as f64,
+            len,
+        })
+        .collect()
+}
+
+// the mean squared distance between the probabilities and the one-hot labels
+pub fn brier_score(probabilities: &[Vec<f64>], labels: &[usize]) -> f64 {
+    probabilities
+        .iter()
+        .zip(labels)
+        .map(|(p, label)| {
+            p.iter()
+                .enumerate()
+                .map(|(i, p)| (p - if i == *label { 1. } else { 0. }).powi(2))
+                .sum::<f64>()
+        })
+        .sum::<f64>()
+        / probabilities.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_and_calibrates_scores() {
+        let scores = [0.9, 0.8, 0.7, 0.6, 0.55, 0.4, 0.3, 0.2];
+        let positives = [true, true, false, true, false, false, true, false];
+        let roc = roc_curve(&scores, &positives);
+        assert_eq!(roc.first(), Some(&(0., 0.)));
+        assert_eq!(roc.last(), Some(&(1., 1.)));
+        // 12 of the 16 positive-negative pairs are ranked right
+        assert!((auc(&roc) - 12. / 16.).abs() < 1e-12);
+        let pr = pr_curve(&scores, &positives);
+        assert!((average_precision(&pr) - (1. + 1. + 3. / 4. + 4. / 7.) / 4.).abs() < 1e-12);
+
+        let Curve::Steps(steps) = fit_isotonic(&scores, &positives) else {
+            unreachable!()
+        };
+        assert!(steps.windows(2).all(|w| w[0].1 <= w[1].1));
+        let Curve::Sigmoid(a, _b) = fit_sigmoid(&scores, &positives) else {
+            unreachable!()
+        };
+        // higher scores are more often positive
+        assert!(a < 0.);
+    }
+}
@@ -0,0 +1,267 @@
+use crate::tree::{
+    sweep_counts, Columns, Criterion, DecisionTree, Hyperparameters, Impurity, Multiway, Tracker,
+};
+
+// axes with at most this many distinct values are categorical for ID3, the
+// others are cut into `ID3_BINS` quantile bins
+const MAX_CATEGORIES: usize = 8;
+const ID3_BINS: usize = 4;
+// C4.5's default confidence factor of 25% as a one-sided normal quantile
+const CONFIDENCE_Z: f64 = 0.6745;
+
+fn entropy(counts: &[usize]) -> f64 {
+    Impurity::Entropy.loss(counts, counts.iter().sum())
+}
+
+// the thresholds between the categories of every column, over all rows
+fn categories(columns: &Columns) -> Vec<Vec<f64>> {
+    columns
+        .columns
+        .iter()
+        .map(|values| {
+            let mut sorted: Vec<f64> = values.iter().copied().filter(|v| !v.is_nan()).collect();
+            sorted.sort_by(f64::total_cmp);
+            let mut distinct = sorted.clone();
+            distinct.dedup();
+            if distinct.len() <= MAX_CATEGORIES {
+                distinct.windows(2).map(|w| (w[0] + w[1]) / 2.).collect()
+            } else {
+                let mut thresholds: Vec<f64> = (1..ID3_BINS)
+                    .map(|bin| sorted[bin * sorted.len() / ID3_BINS])
+                    .filter(|threshold| *threshold > distinct[0])
+                    .collect();
+                thresholds.dedup();
+                thresholds
+            }
+        })
+        .collect()
+}
+
+struct Id3<'a> {
+    columns: &'a Columns,
+    categories: Vec<Vec<f64>>,
+    hyperparameters: &'a Hyperparameters,
+    tracker: &'a Tracker<'a>,
+}
+
+impl Id3<'_> {
+    // the column with the highest information gain among those not used on
+    // the path, `None` if no split gains anything
+    fn find_column(&self, indices: &[usize], used: &[bool]) -> Option<usize> {
+        let columns = self.columns;
+        let parent = entropy(&columns.counts(indices));
+        let min_len = self.hyperparameters.min_elements_per_leaf.max(1);
+        let mut best = None;
+        let mut max_gain = 1e-12;
+        for (column, thresholds) in self.categories.iter().enumerate() {
+            if used[column] || thresholds.is_empty() {
+                continue;
+            }
+            let multiway = Multiway {
+                axis: columns.axises[column],
+                thresholds: thresholds.clone(),
+            };
+            let mut counts = vec![vec![0; columns.classes.len()]; thresholds.len() + 1];
+            for row in indices {
+                counts[multiway.child(columns.columns[column][*row])][columns.labels[*row]] += 1;
+            }
+            let lens: Vec<usize> = counts.iter().map(|counts| counts.iter().sum()).collect();
+            if lens.iter().any(|len| *len > 0 && *len < min_len)
+                || lens.iter().filter(|len| **len > 0).count() < 2
+            {
+                continue;
+            }
+            let gain = parent
+                - counts
+                    .iter()
+                    .zip(&lens)
+                    .map(|(counts, len)| *len as f64 / indices.len() as f64 * entropy(counts))
+                    .sum::<f64>();
+            if gain > max_gain {
+                max_gain = gain;
+                best = Some(column);
+            }
+        }
+        best
+    }
+
+    fn grow(&self, indices: &mut [usize], depth: usize, used: &mut Vec<bool>) -> DecisionTree {
+        let columns = self.columns;
+        let majority = columns.majority(indices);
+        if depth < self.hyperparameters.max_depth
+            && indices.len() > self.hyperparameters.min_elements_to_split
+        {
+            if let Some(column) = self.find_column(indices, used) {
+                let multiway = Multiway {
+                    axis: columns.axises[column],
+                    thresholds: self.categories[column].clone(),
+                };
+                let lens = columns.partition_multiway(indices, &multiway);
+                self.tracker.grown(depth, 0);
+                used[column] = true;
+                let mut rest = &mut indices[..];
+                let mut subtrees = Vec::new();
+                for len in lens {
+                    let (child, others) = rest.split_at_mut(len);
+                    rest = others;
+                    // categories without points at the node predict its majority
+                    subtrees.push(if child.is_empty() {
+                        self.tracker.grown(depth + 1, 0);
+                        DecisionTree::Leave(majority)
+                    } else {
+                        self.grow(child, depth + 1, used)
+                    });
+                }
+                used[column] = false;
+                return DecisionTree::Multiway(multiway, subtrees);
+            }
+        }
+        self.tracker.grown(depth, indices.len());
+        DecisionTree::Leave(majority)
+    }
+}
+
+pub(crate) fn train_id3(
+    columns: &Columns,
+    hyperparameters: &Hyperparameters,
+    tracker: &Tracker,
+) -> DecisionTree {
+    let id3 = Id3 {
+        columns,
+        categories: categories(columns),
+        hyperparameters,
+        tracker,
+    };
+    let mut indices: Vec<usize> = (0..columns.len()).collect();
+    id3.grow(&mut indices, 0, &mut vec![false; columns.axises.len()])
+}
+
+// the threshold with the highest gain ratio among the candidates of the exact
+// search whose information gain is at least the average, as C4.5 does
+fn find_threshold(
+    columns: &Columns,
+    indices: &[usize],
+    hyperparameters: &Hyperparameters,
+) -> Option<(usize, f64)> {
+    let parent = entropy(&columns.counts(indices));
+    // (column, split, gain, gain ratio)
+    let mut candidates = Vec::new();
+    for column in 0..columns.axises.len() {
+        let values = indices
+            .iter()
+            .map(|i| (columns.columns[column][*i], columns.labels[*i]))
+            .collect();
+        sweep_counts(
+            columns,
+            values,
+            indices,
+            hyperparameters,
+            |split, left, left_len, right, right_len| {
+                let len = (left_len + right_len) as f64;
+                let gain = parent
+                    - left_len as f64 / len * entropy(left)
+                    - right_len as f64 / len * entropy(right);
+                let split_info = entropy(&[left_len, right_len]);
+                if gain > 1e-12 {
+                    candidates.push((column, split, gain, gain / split_info));
+                }
+            },
+        );
+    }
+    let average = candidates.iter().map(|c| c.2).sum::<f64>() / candidates.len() as f64;
+    let mut best = None;
+    let mut max_ratio = f64::MIN;
+    for (column, split, gain, ratio) in candidates {
+        if gain >= average && ratio > max_ratio {
+            max_ratio = ratio;
+            best = Some((column, split));
+        }
+    }
+    best
+}
+
+fn grow_c45(
+    columns: &Columns,
+    indices: &mut [usize],
+    depth: usize,
+    hyperparameters: &Hyperparameters,
+    tracker: &Tracker,
+) -> DecisionTree {
+    if depth < hyperparameters.max_depth && indices.len() > hyperparameters.min_elements_to_split {
+        if let Some((column, split)) = find_threshold(columns, indices, hyperparameters) {
+            let mid = columns.partition(indices, column, split);
+            let (left, right) = indices.split_at_mut(mid);
+            tracker.grown(depth, 0);
+            #[cfg(feature = "parallel")]
+            let (left, right) = rayon::join(
+                || grow_c45(columns, left, depth + 1, hyperparameters, tracker),
+                || grow_c45(columns, right, depth + 1, hyperparameters, tracker),
+            );
+            #[cfg(not(feature = "parallel"))]
+            let (left, right) = (
+                grow_c45(columns, left, depth + 1, hyperparameters, tracker),
+                grow_c45(columns, right, depth + 1, hyperparameters, tracker),
+            );
+            let c = Criterion {
+                axis: columns.axises[column],
+                split,
+                p_value: None,
+            };
+            return DecisionTree::Branch(c, Box::new(left), Box::new(right));
+        }
+    }
+    tracker.grown(depth, indices.len());
+    DecisionTree::Leave(columns.majority(indices))
+}
+
+// the upper confidence bound on the errors of a leaf misclassifying `errors`
+// of `len` training points
+fn pessimistic_errors(errors: usize, len: usize) -> f64 {
+    let n = len as f64;
+    let f = errors as f64 / n;
+    let z = CONFIDENCE_Z;
+    let upper = (f + z * z / (2. * n) + z * (f / n - f * f / n + z * z / (4. * n * n)).sqrt())
+        / (1. + z * z / n);
+    upper * n
+}
+
+// bottom-up, a subtree becomes a leaf when the leaf is not expected to make
+// more errors than the subtree; returns the tree and its expected errors
+fn prune_pessimistic(
+    columns: &Columns,
+    indices: &mut [usize],
+    tree: DecisionTree,
+) -> (DecisionTree, f64) {
+    let counts = columns.counts(indices);
+    let errors = indices.len() - counts.iter().max().unwrap();
+    let leaf_errors = pessimistic_errors(errors, indices.len());
+    match tree {
+        DecisionTree::Branch(c, left_tree, right_tree) => {
+            let mid = columns.partition(indices, columns.column(c.axis), c.split);
+            let (left, right) = indices.split_at_mut(mid);
+            let (left_tree, left_errors) = prune_pessimistic(columns, left, *left_tree);
+            let (right_tree, right_errors) = prune_pessimistic(columns, right, *right_tree);
+            if leaf_errors <= left_errors + right_errors {
+                (DecisionTree::Leave(columns.majority(indices)), leaf_errors)
+            } else {
+                (
+                    DecisionTree::Branch(c, Box::new(left_tree), Box::new(right_tree)),
+                    left_errors + right_errors,
+                )
+            }
+        }
+        DecisionTree::Leave(class) => (DecisionTree::Leave(class), leaf_errors),
+        // C4.5 only grows binary axis splits
+        DecisionTree::Oblique(..) | DecisionTree::Multiway(..) => unreachable!(),
+    }
+}
+
+pub(crate) fn train_c45(
+    columns: &Columns,
+    hyperparameters: &Hyperparameters,
+    tracker: &Tracker,
+) -> DecisionTree {
+    let mut indices: Vec<usize> = (0..columns.len()).collect();
+    let tree = grow_c45(columns, &mut indices, 0, hyperparameters, tracker);
+    prune_pessimistic(columns, &mut indices, tree).0
+}
@@ -0,0 +1,433 @@
+use std::iter::repeat_n;
+
+use crate::dataset::DataSet;
+use crate::rules::display_combination;
+use crate::tree::{Criterion, DecisionTree, Hyperplane, Multiway};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Python,
+    C,
+    Sql,
+}
+
+pub fn compile_tree(dataset: &DataSet, tree: &DecisionTree, language: Language) -> String {
+    match language {
+        Language::Rust => to_rust(dataset, tree),
+        Language::Python => to_python(dataset, tree),
+        Language::C => to_c(dataset, tree),
+        Language::Sql => to_sql(dataset, tree),
+    }
+}
+
+fn indent(level: usize) -> String {
+    repeat_n(' ', level * 4).collect()
+}
+
+fn axis_name<'a>(dataset: &'a DataSet, c: &Criterion) -> &'a str {
+    dataset.axis_map.get_by_right(&c.axis).unwrap()
+}
+
+// the children of a multiway split as an if / else-if chain, `branch` formats
+// the test of child `i` (`None` for the last one) and its body
+fn chain(
+    multiway: &Multiway,
+    subtrees: &[DecisionTree],
+    mut branch: impl FnMut(usize, Option<f64>, &DecisionTree) -> String,
+) -> String {
+    subtrees
+        .iter()
+        .enumerate()
+        .map(|(i, subtree)| branch(i, multiway.thresholds.get(i).copied(), subtree))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// w·x over `features[i]`, summed in the same order as `Hyperplane::value`
+fn combination(hyperplane: &Hyperplane) -> String {
+    hyperplane
+        .weights
+        .iter()
+        .map(|(axis, w)| format!("{:?} * features[{}]", w, axis.0))
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+// text for a `//`, `#` or `/* */` comment, a line break would end the first two
+// and `*/` the last one
+fn comment(s: &str) -> String {
+    s.chars()
+        .map(|ch| if ch.is_control() { ' ' } else { ch })
+        .collect::<String>()
+        .replace("*/", "* /")
+}
+
+fn quote(s: &str, quote: char) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push(quote);
+    for ch in s.chars() {
+        match ch {
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            ch if ch == quote => {
+                result.push('\\');
+                result.push(ch);
+            }
+            ch => result.push(ch),
+        }
+    }
+    result.push(quote);
+    result
+}
+
+// thresholds are printed with `{:?}` so that the generated code compares
+// against exactly the same f64 as `classify`
+
+pub fn to_rust(dataset: &DataSet, tree: &DecisionTree) -> String {
+    fn body(dataset: &DataSet, tree: &DecisionTree, level: usize) -> String {
+        let indent_s = indent(level);
+        match tree {
+            DecisionTree::Branch(c, left_tree, right_tree) => format!(
+                "{}if features[{}] < {:?} {{ // {}\n{}\n{}}} else {{\n{}\n{}}}",
+                indent_s,
+                c.axis.0,
+                c.split,
+                comment(axis_name(dataset, c)),
+                body(dataset, left_tree, level + 1),
+                indent_s,
+                body(dataset, right_tree, level + 1),
+                indent_s
+            ),
+            DecisionTree::Oblique(hyperplane, left_tree, right_tree) => format!(
+                "{}if {} < {:?} {{ // {}\n{}\n{}}} else {{\n{}\n{}}}",
+                indent_s,
+                combination(hyperplane),
+                hyperplane.split,
+                comment(&display_combination(dataset, hyperplane)),
+                body(dataset, left_tree, level + 1),
+                indent_s,
+                body(dataset, right_tree, level + 1),
+                indent_s
+            ),
+            DecisionTree::Multiway(multiway, subtrees) => {
+                let name = comment(dataset.axis_map.get_by_right(&multiway.axis).unwrap());
+                let branches = chain(multiway, subtrees, |i, threshold, subtree| {
+                    let test = match (i, threshold) {
+                        (0, Some(t)) => {
+                            format!("if features[{}] < {t:?} {{ // {name}", multiway.axis.0)
+                        }
+                        (_, Some(t)) => {
+                            format!("}} else if features[{}] < {t:?} {{", multiway.axis.0)
+                        }
+                        (_, None) => "} else {".to_owned(),
+                    };
+                    format!("{indent_s}{test}\n{}", body(dataset, subtree, level + 1))
+                });
+                format!("{branches}\n{indent_s}}}")
+            }
+            DecisionTree::Leave(class) => format!(
+                "{}{}",
+                indent_s,
+                quote(dataset.class_map.get_by_right(class).unwrap(), '"')
+            ),
+        }
+    }
+    format!(
+        "pub fn predict(features: &[f64]) -> &'static str {{\n{}\n}}\n",
+        body(dataset, tree, 1)
+    )
+}
+
+pub fn to_python(dataset: &DataSet, tree: &DecisionTree) -> String {
+    fn body(dataset: &DataSet, tree: &DecisionTree, level: usize) -> String {
+        let indent_s = indent(level);
+        match tree {
+            DecisionTree::Branch(c, left_tree, right_tree) => format!(
+                "{}if features[{}] < {:?}:  # {}\n{}\n{}else:\n{}",
+                indent_s,
+                c.axis.0,
+                c.split,
+                comment(axis_name(dataset, c)),
+                body(dataset, left_tree, level + 1),
+                indent_s,
+                body(dataset, right_tree, level + 1),
+            ),
+            DecisionTree::Oblique(hyperplane, left_tree, right_tree) => format!(
+                "{}if {} < {:?}:  # {}\n{}\n{}else:\n{}",
+                indent_s,
+                combination(hyperplane),
+                hyperplane.split,
+                comment(&display_combination(dataset, hyperplane)),
+                body(dataset, left_tree, level + 1),
+                indent_s,
+                body(dataset, right_tree, level + 1),
+            ),
+            DecisionTree::Multiway(multiway, subtrees) => {
+                let name = comment(dataset.axis_map.get_by_right(&multiway.axis).unwrap());
+                chain(multiway, subtrees, |i, threshold, subtree| {
+                    let test = match (i, threshold) {
+                        (0, Some(t)) => {
+                            format!("if features[{}] < {t:?}:  # {name}", multiway.axis.0)
+                        }
+                        (_, Some(t)) => format!("elif features[{}] < {t:?}:", multiway.axis.0),
+                        (_, None) => "else:".to_owned(),
+                    };
+                    format!("{indent_s}{test}\n{}", body(dataset, subtree, level + 1))
+                })
+            }
+            DecisionTree::Leave(class) => format!(
+                "{}return {}",
+                indent_s,
+                quote(dataset.class_map.get_by_right(class).unwrap(), '"')
+            ),
+        }
+    }
+    format!("def predict(features):\n{}\n", body(dataset, tree, 1))
+}
+
+pub fn to_c(dataset: &DataSet, tree: &DecisionTree) -> String {
+    fn body(dataset: &DataSet, tree: &DecisionTree, level: usize) -> String {
+        let indent_s = indent(level);
+        match tree {
+            DecisionTree::Branch(c, left_tree, right_tree) => format!(
+                "{}if (features[{}] < {:?}) {{ /* {} */\n{}\n{}}} else {{\n{}\n{}}}",
+                indent_s,
+                c.axis.0,
+                c.split,
+                comment(axis_name(dataset, c)),
+                body(dataset, left_tree, level + 1),
+                indent_s,
+                body(dataset, right_tree, level + 1),
+                indent_s
+            ),
+            DecisionTree::Oblique(hyperplane, left_tree, right_tree) => format!(
+                "{}if ({} < {:?}) {{ /* {} */\n{}\n{}}} else {{\n{}\n{}}}",
+                indent_s,
+                combination(hyperplane),
+                hyperplane.split,
+                comment(&display_combination(dataset, hyperplane)),
+                body(dataset, left_tree, level + 1),
+                indent_s,
+                body(dataset, right_tree, level + 1),
+                indent_s
+            ),
+            DecisionTree::Multiway(multiway, subtrees) => {
+                let name = comment(dataset.axis_map.get_by_right(&multiway.axis).unwrap());
+                let branches = chain(multiway, subtrees, |i, threshold, subtree| {
+                    let test = match (i, threshold) {
+                        (0, Some(t)) => {
+                            format!("if (features[{}] < {t:?}) {{ /* {name} */", multiway.axis.0)
+                        }
+                        (_, Some(t)) => {
+                            format!("}} else if (features[{}] < {t:?}) {{", multiway.axis.0)
+                        }
+                        (_, None) => "} else {".to_owned(),
+                    };
+                    format!("{indent_s}{test}\n{}", body(dataset, subtree, level + 1))
+                });
+                format!("{branches}\n{indent_s}}}")
+            }
+            DecisionTree::Leave(class) => format!(
+                "{}return {};",
+                indent_s,
+                quote(dataset.class_map.get_by_right(class).unwrap(), '"')
+            ),
+        }
+    }
+    format!(
+        "const char *predict(const double *features) {{\n{}\n}}\n",
+        body(dataset, tree, 1)
+    )
+}
+
+pub fn to_sql(dataset: &DataSet, tree: &DecisionTree) -> String {
+    fn body(dataset: &DataSet, tree: &DecisionTree, level: usize) -> String {
+        let indent_s = indent(level);
+        match tree {
+            DecisionTree::Branch(c, left_tree, right_tree) => format!(
+                "{}CASE WHEN \"{}\" < {:?} THEN\n{}\n{}ELSE\n{}\n{}END",
+                indent_s,
+                axis_name(dataset, c).replace('"', "\"\""),
+                c.split,
+                body(dataset, left_tree, level + 1),
+                indent_s,
+                body(dataset, right_tree, level + 1),
+                indent_s
+            ),
+            DecisionTree::Oblique(hyperplane, left_tree, right_tree) => format!(
+                "{}CASE WHEN {} < {:?} THEN\n{}\n{}ELSE\n{}\n{}END",
+                indent_s,
+                hyperplane
+                    .weights
+                    .iter()
+                    .map(|(axis, w)| format!(
+                        "{:?} * \"{}\"",
+                        w,
+                        dataset
+                            .axis_map
+                            .get_by_right(axis)
+                            .unwrap()
+                            .replace('"', "\"\"")
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(" + "),
+                hyperplane.split,
+                body(dataset, left_tree, level + 1),
+                indent_s,
+                body(dataset, right_tree, level + 1),
+                indent_s
+            ),
+            DecisionTree::Multiway(multiway, subtrees) => {
+                let name = dataset
+                    .axis_map
+                    .get_by_right(&multiway.axis)
+                    .unwrap()
+                    .replace('"', "\"\"");
+                let branches = chain(multiway, subtrees, |i, threshold, subtree| {
+                    let test = match (i, threshold) {
+                        (0, Some(t)) => format!("CASE WHEN \"{name}\" < {t:?} THEN"),
+                        (_, Some(t)) => format!("WHEN \"{name}\" < {t:?} THEN"),
+                        (_, None) => "ELSE".to_owned(),
+                    };
+                    format!("{indent_s}{test}\n{}", body(dataset, subtree, level + 1))
+                });
+                format!("{branches}\n{indent_s}END")
+            }
+            DecisionTree::Leave(class) => format!(
+                "{}'{}'",
+                indent_s,
+                dataset
+                    .class_map
+                    .get_by_right(class)
+                    .unwrap()
+                    .replace('\'', "''")
+            ),
+        }
+    }
+    body(dataset, tree, 0) + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, process::Command};
+
+    use super::*;
+    use crate::dataset::{load_csv, load_dataset};
+    use crate::tree::{classify, train, Algorithm, DataPoint, Hyperparameters, SplitSearch};
+
+    fn check_rust_matches_classify(source: &str, hyperparameters: Hyperparameters) {
+        let mut dataset = load_dataset(load_csv(source.to_owned())).unwrap();
+        // names breaking out of the comments they are written to would break the code
+        dataset.axis_map = dataset
+            .axis_map
+            .iter()
+            .map(|(name, axis)| (format!("{name}\n*/ \r{name}"), *axis))
+            .collect();
+        let tree = train(
+            &dataset.mapped_axis,
+            &dataset.mapped_class,
+            dataset.labelled(),
+            &hyperparameters,
+        );
+        let python = to_python(&dataset, &tree);
+        for line in python.lines().skip(1) {
+            let code = line.trim_start();
+            assert!(
+                ["if ", "elif ", "else:", "return "]
+                    .iter()
+                    .any(|start| code.starts_with(start)),
+                "{line}"
+            );
+        }
+        let c = to_c(&dataset, &tree);
+        for line in c.lines() {
+            // every comment closes at the end of its line
+            assert!(line.matches("*/").count() <= 1, "{line}");
+            assert!(!line.contains("*/") || line.ends_with("*/"), "{line}");
+        }
+
+        let rows = dataset
+            .rows
+            .iter()
+            .map(|(vec, _class)| format!("&{:?}", vec))
+            .collect::<Vec<_>>()
+            .join(",\n");
+        let program = format!(
+            "{}\nfn main() {{\n    let rows: &[&[f64]] = &[{}];\n    for row in rows {{\n        println!(\"{{}}\", predict(row));\n    }}\n}}\n",
+            to_rust(&dataset, &tree),
+            rows
+        );
+
+        let dir = std::env::temp_dir().join(format!(
+            "dectree-codegen-{}-{:?}-{:?}-{}",
+            source,
+            hyperparameters.algorithm,
+            hyperparameters.split_search,
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let src = dir.join("predict.rs");
+        let bin = dir.join("predict");
+        fs::write(&src, program).unwrap();
+        let status = Command::new("rustc")
+            .arg("--edition=2021")
+            .arg("-o")
+            .arg(&bin)
+            .arg(&src)
+            .status()
+            .unwrap();
+        assert!(
+            status.success(),
+            "generated Rust for {source} does not compile"
+        );
+        let output = Command::new(&bin).output().unwrap();
+        assert!(output.status.success());
+        let predicted = String::from_utf8(output.stdout).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        for ((vec, _class), line) in dataset.rows.iter().zip(predicted.lines()) {
+            let result = classify(&tree, vec![DataPoint(vec.clone())]);
+            let (class, _) = result.iter().find(|(_, data)| !data.is_empty()).unwrap();
+            assert_eq!(dataset.class_map.get_by_right(class).unwrap(), line);
+        }
+        assert_eq!(predicted.lines().count(), dataset.rows.len());
+    }
+
+    #[test]
+    fn rust_matches_classify_iris() {
+        check_rust_matches_classify("iris", Hyperparameters::default());
+    }
+
+    #[test]
+    fn rust_matches_classify_wine() {
+        check_rust_matches_classify("wine", Hyperparameters::default());
+    }
+
+    #[test]
+    fn rust_matches_classify_rice() {
+        check_rust_matches_classify("rice", Hyperparameters::default());
+    }
+
+    #[test]
+    fn oblique_rust_matches_classify_rice() {
+        check_rust_matches_classify(
+            "rice",
+            Hyperparameters {
+                split_search: SplitSearch::Oblique,
+                ..Default::default()
+            },
+        );
+    }
+
+    #[test]
+    fn id3_rust_matches_classify_wine() {
+        check_rust_matches_classify(
+            "wine",
+            Hyperparameters {
+                algorithm: Algorithm::Id3,
+                ..Default::default()
+            },
+        );
+    }
+}
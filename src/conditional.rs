@@ -0,0 +1,194 @@
+use crate::tree::{find_split, Columns, Criterion, DecisionTree, Hyperparameters, Tracker};
+
+// ln Γ(x) for x > 0, Lanczos approximation with g = 7
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    let x = x - 1.;
+    let t = x + 7.5;
+    let sum = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64 + 1.));
+    0.5 * (2. * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+// the regularized upper incomplete gamma function Q(a, x), by its series
+// below a + 1 and its continued fraction above
+fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0. {
+        return 1.;
+    }
+    let prefactor = (a * x.ln() - x - ln_gamma(a)).exp();
+    if x < a + 1. {
+        let mut term = 1. / a;
+        let mut sum = term;
+        for n in 1..1000 {
+            term *= x / (a + n as f64);
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        (1. - sum * prefactor).max(0.)
+    } else {
+        // modified Lentz
+        let tiny = 1e-300;
+        let mut b = x + 1. - a;
+        let mut c = 1. / tiny;
+        let mut d = 1. / b;
+        let mut h = d;
+        for i in 1..1000 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1. / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.).abs() < 1e-15 {
+                break;
+            }
+        }
+        prefactor * h
+    }
+}
+
+pub(crate) fn chi_squared_sf(statistic: f64, df: usize) -> f64 {
+    gamma_q(df as f64 / 2., statistic / 2.)
+}
+
+// the p-value of the test of independence between `column` and the class at
+// the node, `None` if either is constant there; rows missing the value are
+// left out. The statistic is the quadratic form of the per-class sums of the
+// values, standardized by their mean and covariance over all permutations of
+// the labels, asymptotically χ² with one degree of freedom less than the
+// classes present
+fn p_value(columns: &Columns, column: usize, indices: &[usize]) -> Option<f64> {
+    let mut counts = vec![0usize; columns.classes.len()];
+    let mut sums = vec![0.; columns.classes.len()];
+    let (mut sum, mut squares, mut len) = (0., 0., 0usize);
+    for row in indices {
+        let value = columns.columns[column][*row];
+        if value.is_nan() {
+            continue;
+        }
+        counts[columns.labels[*row]] += 1;
+        sums[columns.labels[*row]] += value;
+        sum += value;
+        squares += value * value;
+        len += 1;
+    }
+    let n = len as f64;
+    let present = counts.iter().filter(|count| **count > 0).count();
+    let variance = (n * squares - sum * sum) / (n - 1.);
+    if present < 2 || variance <= 0. {
+        return None;
+    }
+    let statistic = counts
+        .iter()
+        .zip(&sums)
+        .filter(|(count, _sum)| **count > 0)
+        .map(|(count, class_sum)| {
+            let p = *count as f64 / n;
+            (class_sum - sum * p).powi(2) / p
+        })
+        .sum::<f64>()
+        / variance;
+    Some(chi_squared_sf(statistic, present - 1))
+}
+
+// the axis most associated with the class, with its p-value adjusted for the number
+// of axes tested by the Šidák correction
+fn select_column(columns: &Columns, indices: &[usize]) -> Option<(usize, f64)> {
+    let p_values: Vec<(usize, f64)> = (0..columns.axises.len())
+        .filter_map(|column| p_value(columns, column, indices).map(|p| (column, p)))
+        .collect();
+    let tests = p_values.len() as f64;
+    let mut best: Option<(usize, f64)> = None;
+    for (column, p) in p_values {
+        if best.is_none_or(|(_column, min_p)| p < min_p) {
+            best = Some((column, p));
+        }
+    }
+    // 1 - (1 - p)^tests without losing small p-values
+    best.map(|(column, p)| (column, -(tests * (-p).ln_1p()).exp_m1()))
+}
+
+fn grow(
+    columns: &Columns,
+    indices: &mut [usize],
+    depth: usize,
+    hyperparameters: &Hyperparameters,
+    tracker: &Tracker,
+) -> DecisionTree {
+    if depth < hyperparameters.max_depth && indices.len() > hyperparameters.min_elements_to_split {
+        let selected = select_column(columns, indices)
+            .filter(|(_column, p)| *p <= hyperparameters.significance)
+            .and_then(|(column, p)| {
+                find_split(columns, column, indices, hyperparameters)
+                    .map(|(_impurity, split)| (column, split, p))
+            });
+        if let Some((column, split, p)) = selected {
+            let mid = columns.partition(indices, column, split);
+            let (left, right) = indices.split_at_mut(mid);
+            tracker.grown(depth, 0);
+            #[cfg(feature = "parallel")]
+            let (left, right) = rayon::join(
+                || grow(columns, left, depth + 1, hyperparameters, tracker),
+                || grow(columns, right, depth + 1, hyperparameters, tracker),
+            );
+            #[cfg(not(feature = "parallel"))]
+            let (left, right) = (
+                grow(columns, left, depth + 1, hyperparameters, tracker),
+                grow(columns, right, depth + 1, hyperparameters, tracker),
+            );
+            let c = Criterion {
+                axis: columns.axises[column],
+                split,
+                p_value: Some(p),
+            };
+            return DecisionTree::Branch(c, Box::new(left), Box::new(right));
+        }
+    }
+    tracker.grown(depth, indices.len());
+    DecisionTree::Leave(columns.majority(indices))
+}
+
+pub(crate) fn train(
+    columns: &Columns,
+    hyperparameters: &Hyperparameters,
+    tracker: &Tracker,
+) -> DecisionTree {
+    let mut indices: Vec<usize> = (0..columns.len()).collect();
+    grow(columns, &mut indices, 0, hyperparameters, tracker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chi_squared_survival_matches_tables() {
+        // 95% and 99% quantiles
+        for (statistic, df, p) in [(3.841, 1, 0.05), (5.991, 2, 0.05), (11.345, 3, 0.01)] {
+            assert!((chi_squared_sf(statistic, df) - p).abs() < 1e-4);
+        }
+        assert!((ln_gamma(5.) - 24f64.ln()).abs() < 1e-12);
+    }
+}
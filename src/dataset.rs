@@ -0,0 +1,209 @@
+use std::{error::Error, fmt};
+
+use bimap::BiMap;
+use rand::{seq::IteratorRandom, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::tree::{Axis, Class, DataPoint, LabelledData};
+
+#[derive(Debug, Clone)]
+pub struct DataSet {
+    pub axises: Vec<String>,
+    pub classes: Vec<String>,
+    pub axis_map: BiMap<String, Axis>,
+    pub class_map: BiMap<String, Class>,
+    pub mapped_axis: Vec<Axis>,
+    pub mapped_class: Vec<Class>,
+    pub rows: Vec<(Vec<f64>, String)>,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Csv(csv::Error),
+    NoAxis,
+    DuplicateColumn(String),
+    Value {
+        line: u64,
+        column: String,
+        value: String,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Csv(e) => write!(f, "{e}"),
+            LoadError::NoAxis => write!(f, "need at least one feature column and a class column"),
+            LoadError::DuplicateColumn(column) => write!(f, "column {column:?} appears twice"),
+            LoadError::Value {
+                line,
+                column,
+                value,
+            } => write!(f, "line {line}: {column} is not a number: {value:?}"),
+        }
+    }
+}
+
+impl Error for LoadError {}
+
+impl From<csv::Error> for LoadError {
+    fn from(value: csv::Error) -> Self {
+        LoadError::Csv(value)
+    }
+}
+
+pub fn load_csv(source: String) -> &'static str {
+    match source.as_str() {
+        "iris" => include_str!("../public/iris.csv"),
+        "wine" => include_str!("../public/wine.csv"),
+        "rice" => include_str!("../public/rice.csv"),
+        _ => unreachable!(),
+    }
+}
+
+// every column but the last one is a numeric feature, the last one is the
+// class label, classes are numbered in order of first appearance
+pub fn load_dataset(csv: &str) -> Result<DataSet, LoadError> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(csv.as_bytes());
+    let mut axises: Vec<String> = rdr.headers()?.iter().map(str::to_owned).collect();
+    if axises.len() < 2 {
+        return Err(LoadError::NoAxis);
+    }
+    axises.pop().unwrap();
+    let mut rows = Vec::new();
+    for record in rdr.records() {
+        let record = record?;
+        let mut vec: Vec<&str> = record.iter().collect();
+        let last = vec.pop().unwrap().to_owned();
+        let values = vec
+            .iter()
+            .zip(axises.iter())
+            .map(|(v, axis)| {
+                v.trim().parse().map_err(|_| LoadError::Value {
+                    line: record.position().map_or(0, |p| p.line()),
+                    column: axis.clone(),
+                    value: (*v).to_owned(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        rows.push((values, last));
+    }
+    DataSet::from_rows(axises, rows)
+}
+
+// the first name that appears twice, names become the keys of a `BiMap`
+pub(crate) fn duplicate(names: &[String]) -> Option<&String> {
+    names
+        .iter()
+        .enumerate()
+        .find(|(i, name)| names[..*i].contains(name))
+        .map(|(_i, name)| name)
+}
+
+// the row numbers of `DataSet::split` as (train, test), both in order
+pub fn split_rows(len: usize, test_len: usize, seed: u64) -> (Vec<usize>, Vec<usize>) {
+    let mut rng = ChaCha12Rng::seed_from_u64(seed);
+    let mut test_rows = (0..len).choose_multiple(&mut rng, test_len);
+    test_rows.sort();
+    let train_rows = (0..len)
+        .filter(|i| test_rows.binary_search(i).is_err())
+        .collect();
+    (train_rows, test_rows)
+}
+
+impl DataSet {
+    // classes are numbered in order of first appearance, axis names have to be
+    // unique
+    pub fn from_rows(
+        axises: Vec<String>,
+        rows: Vec<(Vec<f64>, String)>,
+    ) -> Result<DataSet, LoadError> {
+        if let Some(axis) = duplicate(&axises) {
+            return Err(LoadError::DuplicateColumn(axis.clone()));
+        }
+        let mut classes = Vec::<String>::new();
+        for (_vec, label) in &rows {
+            if !classes.contains(label) {
+                classes.push(label.clone());
+            }
+        }
+        let axis_map = BiMap::from_iter(axises.iter().map(|s| s.to_owned()).zip((0..).map(Axis)));
+        let class_map =
+            BiMap::from_iter(classes.iter().map(|s| s.to_owned()).zip((0..).map(Class)));
+        let mapped_axis = axises
+            .iter()
+            .map(|axis| *axis_map.get_by_left(axis).unwrap())
+            .collect();
+        let mapped_class = classes
+            .iter()
+            .map(|class| *class_map.get_by_left(class).unwrap())
+            .collect();
+        Ok(DataSet {
+            axises,
+            classes,
+            axis_map,
+            class_map,
+            mapped_axis,
+            mapped_class,
+            rows,
+        })
+    }
+
+    pub fn labelled(&self) -> Vec<(DataPoint, Class)> {
+        self.rows
+            .iter()
+            .map(|(vec, class)| {
+                (
+                    DataPoint(vec.clone()),
+                    *self.class_map.get_by_left(class).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    // picks `test_len` rows at random as the test set, the rest is the train set
+    pub fn split(&self, test_len: usize, seed: u64) -> (LabelledData, LabelledData) {
+        let (train_rows, test_rows) = split_rows(self.rows.len(), test_len, seed);
+        let labelled = self.labelled();
+        let pick = |rows: Vec<usize>| rows.into_iter().map(|i| labelled[i].clone()).collect();
+        (pick(train_rows), pick(test_rows))
+    }
+
+    // the same dataset with every axis and class name passed through `rename`,
+    // numbers are kept so a tree trained on one applies to the other
+    pub fn rename(&self, rename: impl Fn(&str) -> String) -> DataSet {
+        let axises: Vec<String> = self.axises.iter().map(|axis| rename(axis)).collect();
+        let classes: Vec<String> = self.classes.iter().map(|class| rename(class)).collect();
+        let axis_map = BiMap::from_iter(
+            self.axises
+                .iter()
+                .zip(axises.iter())
+                .map(|(old, new)| (new.clone(), *self.axis_map.get_by_left(old).unwrap())),
+        );
+        let class_map = BiMap::from_iter(
+            self.classes
+                .iter()
+                .zip(classes.iter())
+                .map(|(old, new)| (new.clone(), *self.class_map.get_by_left(old).unwrap())),
+        );
+        let rows = self
+            .rows
+            .iter()
+            .map(|(vec, label)| {
+                let class = self.class_map.get_by_left(label).unwrap();
+                (vec.clone(), class_map.get_by_right(class).unwrap().clone())
+            })
+            .collect();
+        DataSet {
+            axises,
+            classes,
+            axis_map,
+            class_map,
+            mapped_axis: self.mapped_axis.clone(),
+            mapped_class: self.mapped_class.clone(),
+            rows,
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use rand::{seq::index::sample, Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::eval::Classifier;
+use crate::tree::{
+    predict, Axis, Class, Columns, Criterion, DataPoint, DecisionTree, Hyperparameters,
+};
+
+pub const TREES: usize = 100;
+
+// extremely randomized trees: every node draws one threshold uniformly between
+// the min and max of each of √d random axes and keeps the one with the lowest
+// `impurity`, the trees vote on the class
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExtraTrees {
+    pub classes: Vec<Class>,
+    pub trees: Vec<DecisionTree>,
+}
+
+// (weighted impurity, column, threshold) of a random threshold on `column`,
+// `None` if the column is constant at the node or a side would be too small
+fn random_split(
+    columns: &Columns,
+    column: usize,
+    indices: &[usize],
+    hyperparameters: &Hyperparameters,
+    rng: &mut ChaCha12Rng,
+) -> Option<(f64, usize, f64)> {
+    let values = &columns.columns[column];
+    let (min, max) = indices
+        .iter()
+        .map(|row| values[*row])
+        .filter(|value| !value.is_nan())
+        .fold((f64::MAX, f64::MIN), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+    if min >= max {
+        return None;
+    }
+    let split = rng.gen_range(min..max);
+    let mut left = vec![0; columns.classes.len()];
+    let mut right = vec![0; columns.classes.len()];
+    for row in indices {
+        if values[*row] < split {
+            left[columns.labels[*row]] += 1;
+        } else {
+            right[columns.labels[*row]] += 1;
+        }
+    }
+    let left_len: usize = left.iter().sum();
+    let right_len = indices.len() - left_len;
+    let min_len = hyperparameters.min_elements_per_leaf.max(1);
+    if left_len < min_len || right_len < min_len {
+        return None;
+    }
+    let len = indices.len() as f64;
+    let impurity = left_len as f64 / len * hyperparameters.impurity.loss(&left, left_len)
+        + right_len as f64 / len * hyperparameters.impurity.loss(&right, right_len);
+    Some((impurity, column, split))
+}
+
+fn grow(
+    columns: &Columns,
+    indices: &mut [usize],
+    depth: usize,
+    features: usize,
+    hyperparameters: &Hyperparameters,
+    rng: &mut ChaCha12Rng,
+) -> DecisionTree {
+    let pure = columns.counts(indices).iter().filter(|c| **c > 0).count() <= 1;
+    if !pure
+        && depth < hyperparameters.max_depth
+        && indices.len() > hyperparameters.min_elements_to_split
+    {
+        let mut best: Option<(f64, usize, f64)> = None;
+        for column in sample(rng, columns.axises.len(), features) {
+            if let Some(split) = random_split(columns, column, indices, hyperparameters, rng) {
+                if best.is_none_or(|(impurity, _, _)| split.0 < impurity) {
+                    best = Some(split);
+                }
+            }
+        }
+        if let Some((_impurity, column, split)) = best {
+            let mid = columns.partition(indices, column, split);
+            let (left, right) = indices.split_at_mut(mid);
+            let left = grow(columns, left, depth + 1, features, hyperparameters, rng);
+            let right = grow(columns, right, depth + 1, features, hyperparameters, rng);
+            let c = Criterion {
+                axis: columns.axises[column],
+                split,
+                p_value: None,
+            };
+            return DecisionTree::Branch(c, Box::new(left), Box::new(right));
+        }
+    }
+    DecisionTree::Leave(columns.majority(indices))
+}
+
+impl ExtraTrees {
+    // each tree gets its own generator seeded from `seed`, so the forest is the
+    // same with or without `parallel`; the split search, algorithm and pruning
+    // settings of `hyperparameters` do not apply; `trees` has to be positive
+    pub fn fit(
+        axises: &[Axis],
+        classes: &[Class],
+        data: &[(DataPoint, Class)],
+        hyperparameters: &Hyperparameters,
+        trees: usize,
+        seed: u64,
+    ) -> ExtraTrees {
+        assert!(trees > 0, "an ensemble needs at least one tree");
+        let columns = Columns::new(axises, classes, data);
+        // no axes leaves every tree a single majority-class leaf
+        let features = ((axises.len() as f64).sqrt().round() as usize)
+            .max(1)
+            .min(axises.len());
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+        let seeds: Vec<u64> = (0..trees).map(|_| rng.gen()).collect();
+        let grow_tree = |seed: &u64| {
+            let mut rng = ChaCha12Rng::seed_from_u64(*seed);
+            let mut indices: Vec<usize> = (0..columns.len()).collect();
+            grow(
+                &columns,
+                &mut indices,
+                0,
+                features,
+                hyperparameters,
+                &mut rng,
+            )
+        };
+        #[cfg(feature = "parallel")]
+        let trees = seeds.par_iter().map(grow_tree).collect();
+        #[cfg(not(feature = "parallel"))]
+        let trees = seeds.iter().map(grow_tree).collect();
+        ExtraTrees {
+            classes: classes.to_vec(),
+            trees,
+        }
+    }
+
+    // the number of trees voting for each of `classes`
+    pub fn votes(&self, dp: &DataPoint) -> Vec<usize> {
+        let mut votes = vec![0; self.classes.len()];
+        for tree in &self.trees {
+            let class = predict(tree, dp);
+            if let Some(i) = self.classes.iter().position(|c| *c == class) {
+                votes[i] += 1;
+            }
+        }
+        votes
+    }
+}
+
+impl Classifier for ExtraTrees {
+    // ties go to the first class
+    fn predict(&self, dp: &DataPoint) -> Class {
+        let votes = self.votes(dp);
+        let mut best = 0;
+        for (i, count) in votes.iter().enumerate() {
+            if *count > votes[best] {
+                best = i;
+            }
+        }
+        self.classes[best]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::{load_csv, load_dataset};
+    use crate::eval::accuracy;
+
+    #[test]
+    fn fits_the_same_forest_for_a_seed() {
+        let dataset = load_dataset(load_csv("wine".to_owned())).unwrap();
+        let data = dataset.labelled();
+        let fit = |seed| {
+            ExtraTrees::fit(
+                &dataset.mapped_axis,
+                &dataset.mapped_class,
+                &data,
+                &Hyperparameters::default(),
+                20,
+                seed,
+            )
+        };
+        let forest = fit(0);
+        assert_eq!(format!("{:?}", forest.trees), format!("{:?}", fit(0).trees));
+        assert!(accuracy(&forest, &data) > 0.95);
+    }
+
+    #[test]
+    fn fits_majority_leaves_without_axes() {
+        let data = vec![
+            (DataPoint(Vec::new()), Class(1)),
+            (DataPoint(Vec::new()), Class(1)),
+            (DataPoint(Vec::new()), Class(0)),
+        ];
+        let forest = ExtraTrees::fit(
+            &[],
+            &[Class(0), Class(1)],
+            &data,
+            &Hyperparameters::default(),
+            3,
+            0,
+        );
+        assert_eq!(forest.predict(&DataPoint(Vec::new())), Class(1));
+    }
+}
@@ -0,0 +1,301 @@
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::conditional::chi_squared_sf;
+use crate::tree::{
+    predict, train, Axis, Class, DataPoint, DecisionTree, Hyperparameters, LabelledData,
+};
+
+// a single tree or an ensemble of them
+pub trait Classifier {
+    fn predict(&self, dp: &DataPoint) -> Class;
+}
+
+impl Classifier for DecisionTree {
+    fn predict(&self, dp: &DataPoint) -> Class {
+        predict(self, dp)
+    }
+}
+
+// the fraction of `data` predicted right, NaN if `data` is empty
+pub fn accuracy(model: &(impl Classifier + ?Sized), data: &[(DataPoint, Class)]) -> f64 {
+    let correct = data
+        .iter()
+        .filter(|(dp, class)| model.predict(dp) == *class)
+        .count();
+    correct as f64 / data.len() as f64
+}
+
+// rows are the true class, columns the predicted class, both indexed by the
+// position in `classes`
+pub fn confusion_matrix(
+    model: &(impl Classifier + ?Sized),
+    classes: &[Class],
+    data: &[(DataPoint, Class)],
+) -> Vec<Vec<usize>> {
+    let mut matrix = vec![vec![0; classes.len()]; classes.len()];
+    for (dp, class) in data {
+        let actual = classes.iter().position(|c| c == class).unwrap();
+        let predicted = classes
+            .iter()
+            .position(|c| *c == model.predict(dp))
+            .unwrap();
+        matrix[actual][predicted] += 1;
+    }
+    matrix
+}
+
+// McNemar's test of whether two models have the same accuracy on the same rows,
+// from the rows only one of them gets right
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct McNemar {
+    pub both_correct: usize,
+    pub only_first: usize,
+    pub only_second: usize,
+    pub both_wrong: usize,
+    // χ² with continuity correction, 0 with no disagreements
+    pub statistic: f64,
+    pub p_value: f64,
+}
+
+// below this many disagreements the p-value is the exact two-sided binomial one
+const MCNEMAR_EXACT_BELOW: usize = 25;
+
+pub fn mcnemar(
+    first: &(impl Classifier + ?Sized),
+    second: &(impl Classifier + ?Sized),
+    data: &[(DataPoint, Class)],
+) -> McNemar {
+    let mut counts = [[0; 2]; 2];
+    for (dp, class) in data {
+        let first = first.predict(dp) == *class;
+        let second = second.predict(dp) == *class;
+        counts[usize::from(!first)][usize::from(!second)] += 1;
+    }
+    let (b, c) = (counts[0][1], counts[1][0]);
+    let n = b + c;
+    let statistic = if n == 0 {
+        0.
+    } else {
+        ((b as f64 - c as f64).abs() - 1.).max(0.).powi(2) / n as f64
+    };
+    let p_value = if n < MCNEMAR_EXACT_BELOW {
+        // 2 P(X <= min(b, c)) for X ~ Binomial(n, 1/2)
+        let mut term = 0.5f64.powi(n as i32);
+        let mut tail = 0.;
+        for k in 0..=b.min(c) {
+            tail += term;
+            term *= (n - k) as f64 / (k + 1) as f64;
+        }
+        (2. * tail).min(1.)
+    } else {
+        chi_squared_sf(statistic, 1)
+    };
+    McNemar {
+        both_correct: counts[0][0],
+        only_first: b,
+        only_second: c,
+        both_wrong: counts[1][1],
+        statistic,
+        p_value,
+    }
+}
+
+// shuffles once with `seed`, then yields the row numbers (train, test) of
+// each of the `k` folds; panics unless 2 <= k <= len, so no fold is empty
+pub fn k_fold_rows(len: usize, k: usize, seed: u64) -> Vec<(Vec<usize>, Vec<usize>)> {
+    assert!(
+        (2..=len).contains(&k),
+        "k-fold needs between 2 and {len} folds for {len} rows, got {k}"
+    );
+    let mut rng = ChaCha12Rng::seed_from_u64(seed);
+    let mut index: Vec<usize> = (0..len).collect();
+    index.shuffle(&mut rng);
+    (0..k)
+        .map(|fold| {
+            let mut train = Vec::new();
+            let mut test = Vec::new();
+            for (position, i) in index.iter().enumerate() {
+                if position % k == fold {
+                    test.push(*i);
+                } else {
+                    train.push(*i);
+                }
+            }
+            (train, test)
+        })
+        .collect()
+}
+
+// the rows of `k_fold_rows`
+pub fn k_fold(
+    data: &[(DataPoint, Class)],
+    k: usize,
+    seed: u64,
+) -> Vec<(LabelledData, LabelledData)> {
+    let pick = |rows: Vec<usize>| rows.into_iter().map(|i| data[i].clone()).collect();
+    k_fold_rows(data.len(), k, seed)
+        .into_iter()
+        .map(|(train, test)| (pick(train), pick(test)))
+        .collect()
+}
+
+// mean and population standard deviation
+pub fn mean_std(scores: &[f64]) -> (f64, f64) {
+    let mean = scores.iter().sum::<f64>() / scores.len() as f64;
+    let std = (scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / scores.len() as f64).sqrt();
+    (mean, std)
+}
+
+// the test accuracy of every fold, `k` as in `k_fold_rows`
+pub fn cross_validate(
+    axises: &[Axis],
+    classes: &[Class],
+    data: &[(DataPoint, Class)],
+    k: usize,
+    seed: u64,
+    hyperparameters: &Hyperparameters,
+) -> Vec<f64> {
+    k_fold(data, k, seed)
+        .into_iter()
+        .map(|(train_data, test_data)| {
+            let tree = train(axises, classes, train_data, hyperparameters);
+            accuracy(&tree, &test_data)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CurvePoint {
+    // the training set fraction or the hyperparameter value
+    pub x: f64,
+    pub train_accuracy: f64,
+    pub test_accuracy: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CurveKind {
+    Learning,
+    Validation(Parameter),
+}
+
+impl CurveKind {
+    // the training set fractions or the hyperparameter values of the points
+    pub fn xs(self) -> Vec<f64> {
+        match self {
+            CurveKind::Learning => (1..=10).map(|i| i as f64 / 10.).collect(),
+            CurveKind::Validation(parameter) => {
+                parameter.values().into_iter().map(|v| v as f64).collect()
+            }
+        }
+    }
+}
+
+// trains on growing random fractions of `train_data`, each one containing the
+// previous ones, and scores every tree on its own training subset and on `test_data`
+pub fn learning_curve(
+    axises: &[Axis],
+    classes: &[Class],
+    train_data: &[(DataPoint, Class)],
+    test_data: &[(DataPoint, Class)],
+    fractions: &[f64],
+    seed: u64,
+    hyperparameters: &Hyperparameters,
+) -> Vec<CurvePoint> {
+    let mut rng = ChaCha12Rng::seed_from_u64(seed);
+    let mut shuffled = train_data.to_vec();
+    shuffled.shuffle(&mut rng);
+    fractions
+        .iter()
+        .map(|fraction| {
+            let len =
+                ((shuffled.len() as f64 * fraction).round() as usize).clamp(1, shuffled.len());
+            let subset = &shuffled[..len];
+            let tree = train(axises, classes, subset.to_vec(), hyperparameters);
+            CurvePoint {
+                x: *fraction,
+                train_accuracy: accuracy(&tree, subset),
+                test_accuracy: accuracy(&tree, test_data),
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Parameter {
+    MaxDepth,
+    MinElementsToSplit,
+}
+
+impl Parameter {
+    pub fn values(self) -> Vec<usize> {
+        match self {
+            Parameter::MaxDepth => (1..=15).collect(),
+            Parameter::MinElementsToSplit => vec![1, 2, 3, 5, 10, 20, 50, 100, 200],
+        }
+    }
+
+    pub fn apply(self, hyperparameters: &Hyperparameters, value: usize) -> Hyperparameters {
+        match self {
+            Parameter::MaxDepth => Hyperparameters {
+                max_depth: value,
+                ..*hyperparameters
+            },
+            Parameter::MinElementsToSplit => Hyperparameters {
+                min_elements_to_split: value,
+                ..*hyperparameters
+            },
+        }
+    }
+}
+
+// trains on all of `train_data` once per value of `parameter`, the other
+// hyperparameters are taken from `hyperparameters`
+pub fn validation_curve(
+    axises: &[Axis],
+    classes: &[Class],
+    train_data: &[(DataPoint, Class)],
+    test_data: &[(DataPoint, Class)],
+    parameter: Parameter,
+    values: &[usize],
+    hyperparameters: &Hyperparameters,
+) -> Vec<CurvePoint> {
+    values
+        .iter()
+        .map(|value| {
+            let hyperparameters = parameter.apply(hyperparameters, *value);
+            let tree = train(axises, classes, train_data.to_vec(), &hyperparameters);
+            CurvePoint {
+                x: *value as f64,
+                train_accuracy: accuracy(&tree, train_data),
+                test_accuracy: accuracy(&tree, test_data),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mcnemar_counts_disagreements() {
+        // 30 rows of class 0 and 10 of class 1, each constant model gets one of them right
+        let data: LabelledData = (0..40)
+            .map(|i| (DataPoint(vec![i as f64]), Class(i32::from(i >= 30))))
+            .collect();
+        let first = DecisionTree::Leave(Class(0));
+        let second = DecisionTree::Leave(Class(1));
+        let test = mcnemar(&first, &second, &data);
+        assert_eq!((test.only_first, test.only_second), (30, 10));
+        assert!((test.statistic - 9.025).abs() < 1e-9);
+        assert!((test.p_value - 0.002663).abs() < 1e-5);
+        // exact: 2 / 2^6
+        let test = mcnemar(&first, &second, &data[..6]);
+        assert!((test.p_value - 0.03125).abs() < 1e-12);
+        assert_eq!(mcnemar(&first, &first, &data).p_value, 1.);
+    }
+}
@@ -0,0 +1,229 @@
+use std::{collections::VecDeque, ops::Range};
+
+use crate::rules::{narrow, Condition};
+use crate::tree::{
+    curves, find_criterion, Algorithm, Axis, Class, Columns, Criterion, DataPoint, DecisionTree,
+    Hyperparameters, ImpurityCurve, SplitSearch,
+};
+
+// one expanded node, nodes are numbered breadth-first starting with the root at 0
+#[derive(Debug, Clone)]
+pub struct Step {
+    pub node: usize,
+    pub depth: usize,
+    // region of the node, as the tests on the path from the root
+    pub conditions: Vec<Condition>,
+    // class counts in the node, in the order of `classes`
+    pub counts: Vec<usize>,
+    pub impurity: f64,
+    // every threshold tried on every axis, empty when the node was too deep or
+    // too small to be split
+    pub curves: Vec<ImpurityCurve>,
+    // `None` when the node became a leaf
+    pub criterion: Option<Criterion>,
+    pub class: Class,
+}
+
+enum Node {
+    Pending(Class),
+    Branch(Criterion, usize, usize),
+    Leave(Class),
+}
+
+struct Pending {
+    node: usize,
+    range: Range<usize>,
+    depth: usize,
+    conditions: Vec<Condition>,
+}
+
+// grows the same tree as `train` with the exact search and no pruning, one node per call to
+// `next` in breadth-first order, so it can be shown while it grows
+pub struct Growth {
+    columns: Columns,
+    hyperparameters: Hyperparameters,
+    indices: Vec<usize>,
+    nodes: Vec<Node>,
+    frontier: VecDeque<Pending>,
+}
+
+impl Growth {
+    // whether `train` grows the tree the way `Growth` does, before pruning
+    pub fn supports(hyperparameters: &Hyperparameters) -> bool {
+        hyperparameters.algorithm == Algorithm::Cart
+            && hyperparameters.split_search == SplitSearch::Exact
+    }
+
+    pub fn new(
+        axises: &[Axis],
+        classes: &[Class],
+        vec: Vec<(DataPoint, Class)>,
+        hyperparameters: &Hyperparameters,
+    ) -> Self {
+        let columns = Columns::new(axises, classes, &vec);
+        drop(vec);
+        let indices: Vec<usize> = (0..columns.len()).collect();
+        let root = Node::Pending(columns.majority(&indices));
+        Growth {
+            frontier: VecDeque::from([Pending {
+                node: 0,
+                range: 0..indices.len(),
+                depth: 0,
+                conditions: Vec::new(),
+            }]),
+            columns,
+            hyperparameters: *hyperparameters,
+            indices,
+            nodes: vec![root],
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frontier.is_empty()
+    }
+
+    // the tree grown so far, nodes not expanded yet are leaves of their majority class
+    pub fn tree(&self) -> DecisionTree {
+        self.subtree(0)
+    }
+
+    fn subtree(&self, node: usize) -> DecisionTree {
+        match &self.nodes[node] {
+            Node::Pending(class) | Node::Leave(class) => DecisionTree::Leave(*class),
+            Node::Branch(c, left, right) => DecisionTree::Branch(
+                *c,
+                Box::new(self.subtree(*left)),
+                Box::new(self.subtree(*right)),
+            ),
+        }
+    }
+
+    fn push(&mut self, range: Range<usize>, depth: usize, conditions: Vec<Condition>) -> usize {
+        let node = self.nodes.len();
+        let class = self.columns.majority(&self.indices[range.clone()]);
+        self.nodes.push(Node::Pending(class));
+        self.frontier.push_back(Pending {
+            node,
+            range,
+            depth,
+            conditions,
+        });
+        node
+    }
+}
+
+impl Iterator for Growth {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Step> {
+        let pending = self.frontier.pop_front()?;
+        let columns = &self.columns;
+        let indices = &mut self.indices[pending.range.clone()];
+        let counts = columns.counts(indices);
+        let class = columns.majority(indices);
+        let mut step = Step {
+            node: pending.node,
+            depth: pending.depth,
+            conditions: pending.conditions.clone(),
+            impurity: self.hyperparameters.impurity.loss(&counts, indices.len()),
+            counts,
+            curves: Vec::new(),
+            criterion: None,
+            class,
+        };
+        if pending.depth < self.hyperparameters.max_depth
+            && indices.len() > self.hyperparameters.min_elements_to_split
+        {
+            step.curves = curves(columns, indices, &self.hyperparameters);
+            if let Some((column, split)) = find_criterion(columns, indices, &self.hyperparameters) {
+                let mid = columns.partition(indices, column, split);
+                let c = Criterion {
+                    axis: columns.axises[column],
+                    split,
+                    p_value: None,
+                };
+                let start = pending.range.start;
+                let left = self.push(
+                    start..start + mid,
+                    pending.depth + 1,
+                    narrow(&pending.conditions, c.axis, None, Some(split)),
+                );
+                let right = self.push(
+                    start + mid..pending.range.end,
+                    pending.depth + 1,
+                    narrow(&pending.conditions, c.axis, Some(split), None),
+                );
+                self.nodes[pending.node] = Node::Branch(c, left, right);
+                step.criterion = Some(c);
+                return Some(step);
+            }
+        }
+        self.nodes[pending.node] = Node::Leave(class);
+        Some(step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::{load_csv, load_dataset};
+    use crate::rules::regions;
+    use crate::tree::{train, Impurity};
+
+    fn same_tree(a: &DecisionTree, b: &DecisionTree) -> bool {
+        match (a, b) {
+            (
+                DecisionTree::Branch(a, a_left, a_right),
+                DecisionTree::Branch(b, b_left, b_right),
+            ) => {
+                a.axis == b.axis
+                    && a.split == b.split
+                    && same_tree(a_left, b_left)
+                    && same_tree(a_right, b_right)
+            }
+            (DecisionTree::Leave(a), DecisionTree::Leave(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn grows_the_same_tree_as_train() {
+        for (source, impurity) in [
+            ("iris", Impurity::Gini),
+            ("wine", Impurity::Gini),
+            ("rice", Impurity::Gini),
+            ("wine", Impurity::Entropy),
+        ] {
+            let dataset = load_dataset(load_csv(source.to_owned())).unwrap();
+            let hyperparameters = Hyperparameters {
+                impurity,
+                ..Hyperparameters::default()
+            };
+            let tree = train(
+                &dataset.mapped_axis,
+                &dataset.mapped_class,
+                dataset.labelled(),
+                &hyperparameters,
+            );
+            let mut growth = Growth::new(
+                &dataset.mapped_axis,
+                &dataset.mapped_class,
+                dataset.labelled(),
+                &hyperparameters,
+            );
+            let steps: Vec<Step> = growth.by_ref().collect();
+            assert!(growth.is_finished());
+            assert!(same_tree(&growth.tree(), &tree), "{source} {impurity:?}");
+            let regions = regions(&tree);
+            assert_eq!(steps.len(), regions.len());
+            for step in steps {
+                let region = &regions[step.node];
+                assert_eq!(step.depth, region.depth);
+                assert_eq!(
+                    step.criterion.map(|c| (c.axis, c.split)),
+                    region.criterion.map(|c| (c.axis, c.split))
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,297 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::tree::{Columns, Criterion, DecisionTree, Hyperparameters, Tracker};
+
+pub(crate) const MAX_BINS: usize = 256;
+
+// every axis quantized once into at most `MAX_BINS` quantile bins, a value is
+// in bin `b` when `thresholds[b - 1] <= value < thresholds[b]`; missing values
+// (NaN) get a bin of their own after the others, so they always go right like
+// in `predict`
+struct Bins {
+    thresholds: Vec<Vec<f64>>,
+    codes: Vec<Vec<u8>>,
+}
+
+impl Bins {
+    fn new(columns: &Columns, max_bins: usize) -> Self {
+        assert!(
+            (2..=MAX_BINS).contains(&max_bins),
+            "the histogram needs between 2 and {MAX_BINS} bins, got {max_bins}"
+        );
+        let thresholds: Vec<Vec<f64>> = columns
+            .columns
+            .iter()
+            .map(|values| {
+                let mut sorted: Vec<f64> = values.iter().copied().filter(|v| !v.is_nan()).collect();
+                // the missing bin counts towards `max_bins`
+                let max_bins = if sorted.len() < values.len() {
+                    max_bins - 1
+                } else {
+                    max_bins
+                };
+                sorted.sort_by(|a, b| a.total_cmp(b));
+                let mut distinct = sorted.clone();
+                distinct.dedup();
+                if distinct.len() <= max_bins {
+                    // one bin per distinct value, split halfway between neighbours
+                    distinct.windows(2).map(|w| (w[0] + w[1]) / 2.).collect()
+                } else {
+                    let mut cuts: Vec<f64> = (1..max_bins)
+                        .map(|k| sorted[k * sorted.len() / max_bins])
+                        .collect();
+                    cuts.dedup();
+                    cuts
+                }
+            })
+            .collect();
+        let codes = columns
+            .columns
+            .iter()
+            .zip(thresholds.iter())
+            .map(|(values, thresholds)| {
+                values
+                    .iter()
+                    .map(|value| {
+                        if value.is_nan() {
+                            thresholds.len() as u8 + 1
+                        } else {
+                            thresholds.partition_point(|t| t <= value) as u8
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Bins { thresholds, codes }
+    }
+
+    // the regular bins of `column` and the missing one
+    fn len(&self, column: usize) -> usize {
+        self.thresholds[column].len() + 2
+    }
+}
+
+// class counts per (column, bin), flattened as `[bin * classes + class]`
+#[derive(Clone)]
+struct Histogram(Vec<Vec<usize>>);
+
+impl Histogram {
+    fn new(bins: &Bins, classes: usize, labels: &[usize], indices: &[usize]) -> Self {
+        Histogram(
+            bins.codes
+                .iter()
+                .enumerate()
+                .map(|(column, codes)| {
+                    let mut counts = vec![0; bins.len(column) * classes];
+                    for i in indices {
+                        counts[codes[*i] as usize * classes + labels[*i]] += 1;
+                    }
+                    counts
+                })
+                .collect(),
+        )
+    }
+
+    // the sibling-subtraction trick: only the smaller child is counted
+    fn subtract(&self, other: &Histogram) -> Histogram {
+        Histogram(
+            self.0
+                .iter()
+                .zip(other.0.iter())
+                .map(|(a, b)| a.iter().zip(b.iter()).map(|(a, b)| a - b).collect())
+                .collect(),
+        )
+    }
+}
+
+// returns the lowest (impurity, bin) on `column`, splitting after `bin`, one
+// of the `splits` bins with a threshold after them
+fn find_split(
+    counts: &[usize],
+    splits: usize,
+    classes: usize,
+    total: &[usize],
+    hyperparameters: &Hyperparameters,
+) -> Option<(f64, usize)> {
+    let min_len = hyperparameters.min_elements_per_leaf.max(1);
+    let len: usize = total.iter().sum();
+    let mut left = vec![0; classes];
+    let mut left_len = 0;
+    let mut best = None;
+    let mut min_impurity = f64::MAX;
+    for bin in 0..splits {
+        for class in 0..classes {
+            left[class] += counts[bin * classes + class];
+            left_len += counts[bin * classes + class];
+        }
+        let right_len = len - left_len;
+        if left_len < min_len || right_len < min_len {
+            continue;
+        }
+        let right: Vec<usize> = total.iter().zip(left.iter()).map(|(t, l)| t - l).collect();
+        let impurity = ((left_len as f64) / (len as f64))
+            * hyperparameters.impurity.loss(&left, left_len)
+            + ((right_len as f64) / (len as f64))
+                * hyperparameters.impurity.loss(&right, right_len);
+        if impurity < min_impurity {
+            min_impurity = impurity;
+            best = Some((impurity, bin));
+        }
+    }
+    best
+}
+
+fn find_criterion(
+    histogram: &Histogram,
+    bins: &Bins,
+    classes: usize,
+    total: &[usize],
+    hyperparameters: &Hyperparameters,
+) -> Option<(usize, usize)> {
+    #[cfg(feature = "parallel")]
+    let splits: Vec<_> = histogram
+        .0
+        .par_iter()
+        .zip(&bins.thresholds)
+        .map(|(counts, thresholds)| {
+            find_split(counts, thresholds.len(), classes, total, hyperparameters)
+        })
+        .collect();
+    #[cfg(not(feature = "parallel"))]
+    let splits: Vec<_> = histogram
+        .0
+        .iter()
+        .zip(&bins.thresholds)
+        .map(|(counts, thresholds)| {
+            find_split(counts, thresholds.len(), classes, total, hyperparameters)
+        })
+        .collect();
+    let mut min_impurity = f64::MAX;
+    let mut best = None;
+    for (column, split) in splits.into_iter().enumerate() {
+        if let Some((impurity, bin)) = split {
+            if impurity < min_impurity {
+                min_impurity = impurity;
+                best = Some((column, bin));
+            }
+        }
+    }
+    best
+}
+
+struct Grower<'a> {
+    columns: &'a Columns,
+    bins: Bins,
+    hyperparameters: &'a Hyperparameters,
+    tracker: &'a Tracker<'a>,
+}
+
+impl Grower<'_> {
+    fn grow(&self, indices: &mut [usize], histogram: Histogram, depth: usize) -> DecisionTree {
+        let columns = self.columns;
+        let classes = columns.classes.len();
+        if depth < self.hyperparameters.max_depth
+            && indices.len() > self.hyperparameters.min_elements_to_split
+        {
+            let total = columns.counts(indices);
+            if let Some((column, bin)) = find_criterion(
+                &histogram,
+                &self.bins,
+                classes,
+                &total,
+                self.hyperparameters,
+            ) {
+                let codes = &self.bins.codes[column];
+                let mut mid = 0;
+                for i in 0..indices.len() {
+                    if codes[indices[i]] as usize <= bin {
+                        indices.swap(i, mid);
+                        mid += 1;
+                    }
+                }
+                let (left, right) = indices.split_at_mut(mid);
+                let (left_histogram, right_histogram) = if left.len() <= right.len() {
+                    let small = Histogram::new(&self.bins, classes, &columns.labels, left);
+                    let large = histogram.subtract(&small);
+                    (small, large)
+                } else {
+                    let small = Histogram::new(&self.bins, classes, &columns.labels, right);
+                    let large = histogram.subtract(&small);
+                    (large, small)
+                };
+                drop(histogram);
+                self.tracker.grown(depth, 0);
+                #[cfg(feature = "parallel")]
+                let (left, right) = rayon::join(
+                    || self.grow(left, left_histogram, depth + 1),
+                    || self.grow(right, right_histogram, depth + 1),
+                );
+                #[cfg(not(feature = "parallel"))]
+                let (left, right) = (
+                    self.grow(left, left_histogram, depth + 1),
+                    self.grow(right, right_histogram, depth + 1),
+                );
+                let c = Criterion {
+                    axis: columns.axises[column],
+                    split: self.bins.thresholds[column][bin],
+                    p_value: None,
+                };
+                return DecisionTree::Branch(c, Box::new(left), Box::new(right));
+            }
+        }
+        self.tracker.grown(depth, indices.len());
+        DecisionTree::Leave(columns.majority(indices))
+    }
+}
+
+pub(crate) fn train(
+    columns: &Columns,
+    max_bins: usize,
+    hyperparameters: &Hyperparameters,
+    tracker: &Tracker,
+) -> DecisionTree {
+    let grower = Grower {
+        columns,
+        bins: Bins::new(columns, max_bins),
+        hyperparameters,
+        tracker,
+    };
+    let mut indices: Vec<usize> = (0..columns.len()).collect();
+    let histogram = Histogram::new(
+        &grower.bins,
+        columns.classes.len(),
+        &columns.labels,
+        &indices,
+    );
+    grower.grow(&mut indices, histogram, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::accuracy;
+    use crate::tree::{train as train_tree, Class, DataPoint, SplitSearch};
+
+    #[test]
+    fn sends_missing_values_right_like_predict() {
+        // class 1 is high or missing on the only axis
+        let data: Vec<(DataPoint, Class)> = (0..40)
+            .map(|i| match i % 4 {
+                0 => (DataPoint(vec![f64::NAN]), Class(1)),
+                1 => (DataPoint(vec![10. + i as f64]), Class(1)),
+                _ => (DataPoint(vec![i as f64 / 10.]), Class(0)),
+            })
+            .collect();
+        let axises = [crate::tree::Axis(0)];
+        let classes = [Class(0), Class(1)];
+        for max_bins in [4, 16, MAX_BINS] {
+            let hyperparameters = Hyperparameters {
+                split_search: SplitSearch::Histogram { max_bins },
+                ..Hyperparameters::default()
+            };
+            let tree = train_tree(&axises, &classes, data.clone(), &hyperparameters);
+            assert_eq!(accuracy(&tree, &data), 1., "{max_bins} bins");
+        }
+    }
+}
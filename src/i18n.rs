@@ -0,0 +1,105 @@
+// languages of the UI, the bundled datasets are written in Chinese
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Locale {
+    #[default]
+    ZhCn,
+    En,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::ZhCn, Locale::En];
+
+    // BCP 47 tag
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::En => "en",
+        }
+    }
+
+    // matches on the language only, so "zh-TW" is Chinese and "en-GB" English
+    pub fn from_code(code: &str) -> Option<Locale> {
+        let language = code.split(['-', '_']).next()?.to_ascii_lowercase();
+        match language.as_str() {
+            "zh" => Some(Locale::ZhCn),
+            "en" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    // the name of the language in itself
+    pub fn name(self) -> &'static str {
+        match self {
+            Locale::ZhCn => "简体中文",
+            Locale::En => "English",
+        }
+    }
+
+    pub fn pick<T>(self, zh: T, en: T) -> T {
+        match self {
+            Locale::ZhCn => zh,
+            Locale::En => en,
+        }
+    }
+}
+
+// `tr!(locale, "中文 {x}", "English {x}", ...)` formats the text of `locale`
+#[cfg(feature = "web")]
+macro_rules! tr {
+    ($locale:expr, $zh:literal, $en:literal $(, $args:expr)* $(,)?) => {
+        match $locale {
+            $crate::i18n::Locale::ZhCn => format!($zh $(, $args)*),
+            $crate::i18n::Locale::En => format!($en $(, $args)*),
+        }
+    };
+}
+#[cfg(feature = "web")]
+pub(crate) use tr;
+
+// (Chinese, English) names of the columns and classes of the bundled datasets
+const DATASET_NAMES: [(&str, &str); 30] = [
+    ("花萼长度", "sepal length"),
+    ("花萼宽度", "sepal width"),
+    ("花瓣长度", "petal length"),
+    ("花瓣宽度", "petal width"),
+    ("山鸢尾 (Iris setosa)", "Iris setosa"),
+    ("变色鸢尾 (Iris versicolor)", "Iris versicolor"),
+    ("维吉尼亚鸢尾 (Iris virginica)", "Iris virginica"),
+    ("酒精含量", "alcohol"),
+    ("苹果酸含量", "malic acid"),
+    ("灰分含量", "ash"),
+    ("灰分碱度", "alcalinity of ash"),
+    ("镁含量", "magnesium"),
+    ("总酚含量", "total phenols"),
+    ("黄酮类化合物含量", "flavanoids"),
+    ("非黄酮类酚含量", "nonflavanoid phenols"),
+    ("原花青素含量", "proanthocyanins"),
+    ("色度", "color intensity"),
+    ("色相", "hue"),
+    ("浊度", "OD280/OD315"),
+    ("脯氨酸含量", "proline"),
+    ("类型一", "type 1"),
+    ("类型二", "type 2"),
+    ("类型三", "type 3"),
+    ("面积", "area"),
+    ("周长", "perimeter"),
+    ("主轴长", "major axis length"),
+    ("副轴长", "minor axis length"),
+    ("离心率", "eccentricity"),
+    ("最小凸包面积", "convex area"),
+    ("图片面积占比", "extent"),
+];
+
+// the display name of a column or class of a bundled dataset, other names are
+// returned as they are
+pub fn dataset_name(locale: Locale, name: &str) -> String {
+    let translated = match locale {
+        Locale::ZhCn => None,
+        Locale::En => DATASET_NAMES
+            .iter()
+            .find(|(zh, _en)| *zh == name)
+            .map(|(_zh, en)| *en),
+    };
+    translated.unwrap_or(name).to_owned()
+}
@@ -0,0 +1,161 @@
+use rand::{seq::index::sample, Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::tree::{Axis, DataPoint};
+
+// the defaults of the original paper
+pub const TREES: usize = 100;
+pub const SAMPLE_SIZE: usize = 256;
+
+const EULER_GAMMA: f64 = 0.577_215_664_901_532_9;
+
+// the average path length of an unsuccessful search in a binary search tree
+// of `n` points, used to normalize path lengths and for the points left in a leaf
+fn average_path_length(n: usize) -> f64 {
+    match n {
+        0 | 1 => 0.,
+        2 => 1.,
+        n => {
+            let n = n as f64;
+            2. * ((n - 1.).ln() + EULER_GAMMA) - 2. * (n - 1.) / n
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    // missing values go right like they do in a decision tree
+    Split(Axis, f64, Box<Node>, Box<Node>),
+    Leaf(usize),
+}
+
+impl Node {
+    fn grow(
+        axises: &[Axis],
+        data: &[&DataPoint],
+        depth: usize,
+        max_depth: usize,
+        rng: &mut ChaCha12Rng,
+    ) -> Node {
+        if depth >= max_depth || data.len() <= 1 {
+            return Node::Leaf(data.len());
+        }
+        // (axis, min, max) of the axes that still vary
+        let ranges: Vec<(Axis, f64, f64)> = axises
+            .iter()
+            .filter_map(|axis| {
+                let (min, max) = data
+                    .iter()
+                    .map(|dp| dp[axis])
+                    .filter(|value| !value.is_nan())
+                    .fold((f64::MAX, f64::MIN), |(min, max), value| {
+                        (min.min(value), max.max(value))
+                    });
+                (min < max).then_some((*axis, min, max))
+            })
+            .collect();
+        if ranges.is_empty() {
+            return Node::Leaf(data.len());
+        }
+        let (axis, min, max) = ranges[rng.gen_range(0..ranges.len())];
+        let split = rng.gen_range(min..max);
+        let (left, right): (Vec<&DataPoint>, Vec<&DataPoint>) =
+            data.iter().partition(|dp| dp[axis] < split);
+        Node::Split(
+            axis,
+            split,
+            Box::new(Node::grow(axises, &left, depth + 1, max_depth, rng)),
+            Box::new(Node::grow(axises, &right, depth + 1, max_depth, rng)),
+        )
+    }
+
+    fn path_length(&self, dp: &DataPoint) -> f64 {
+        match self {
+            Node::Split(axis, split, left, right) => {
+                let child = if dp[axis] < *split { left } else { right };
+                1. + child.path_length(dp)
+            }
+            Node::Leaf(len) => average_path_length(*len),
+        }
+    }
+}
+
+// random-split trees isolating each point, anomalies are isolated in fewer splits
+#[derive(Debug, Clone)]
+pub struct IsolationForest {
+    trees: Vec<Node>,
+    sample_size: usize,
+}
+
+impl IsolationForest {
+    // every tree is grown on `sample_size` points drawn without replacement,
+    // up to the average depth of a balanced tree over them
+    pub fn fit(
+        axises: &[Axis],
+        data: &[DataPoint],
+        trees: usize,
+        sample_size: usize,
+        seed: u64,
+    ) -> IsolationForest {
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+        let sample_size = sample_size.min(data.len());
+        let max_depth = (sample_size.max(2) as f64).log2().ceil() as usize;
+        let trees = (0..trees)
+            .map(|_| {
+                let sample: Vec<&DataPoint> = sample(&mut rng, data.len(), sample_size)
+                    .into_iter()
+                    .map(|i| &data[i])
+                    .collect();
+                Node::grow(axises, &sample, 0, max_depth, &mut rng)
+            })
+            .collect();
+        IsolationForest { trees, sample_size }
+    }
+
+    // 2^(-E[h(x)] / c(ψ)), close to 1 for anomalies and well below 0.5 for
+    // ordinary points
+    pub fn score(&self, dp: &DataPoint) -> f64 {
+        let normalizer = average_path_length(self.sample_size);
+        if self.trees.is_empty() || normalizer == 0. {
+            return 0.5;
+        }
+        let mean = self
+            .trees
+            .iter()
+            .map(|tree| tree.path_length(dp))
+            .sum::<f64>()
+            / self.trees.len() as f64;
+        2f64.powf(-mean / normalizer)
+    }
+}
+
+// the anomaly score of every row with the default forest
+pub fn anomaly_scores(axises: &[Axis], data: &[DataPoint], seed: u64) -> Vec<f64> {
+    let forest = IsolationForest::fit(axises, data, TREES, SAMPLE_SIZE, seed);
+    data.iter().map(|dp| forest.score(dp)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::{load_csv, load_dataset};
+
+    #[test]
+    fn scores_a_far_point_highest() {
+        let dataset = load_dataset(load_csv("iris".to_owned())).unwrap();
+        let mut data: Vec<DataPoint> = dataset
+            .labelled()
+            .into_iter()
+            .map(|(dp, _class)| dp)
+            .collect();
+        let mut outlier = data[0].clone();
+        outlier.0[0] += 10.;
+        data.push(outlier);
+        let scores = anomaly_scores(&dataset.mapped_axis, &data, 0);
+        let highest = (0..scores.len())
+            .max_by(|a, b| scores[*a].total_cmp(&scores[*b]))
+            .unwrap();
+        assert_eq!(highest, data.len() - 1);
+        assert!(scores[highest] > 0.6);
+    }
+}
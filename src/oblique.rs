@@ -0,0 +1,182 @@
+use crate::tree::{sweep_values, Columns, Hyperparameters, Hyperplane};
+
+// added to the diagonal of the within-class covariance, in standardized units,
+// so that collinear axes still give a direction
+const RIDGE: f64 = 1e-4;
+
+// solves `a x = b` by Gaussian elimination with partial pivoting
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|i, j| a[*i][col].abs().total_cmp(&a[*j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let pivot_row = a[col].clone();
+        for row in col + 1..n {
+            let factor = a[row][col] / pivot_row[col];
+            for (value, pivot) in a[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *value -= factor * pivot;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = vec![0.; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+// Fisher's discriminant of class `class` against the others, as weights by
+// column on the original scale, the largest one being ±1
+fn discriminant(
+    columns: &Columns,
+    rows: &[usize],
+    active: &[(usize, f64, f64)],
+    class: usize,
+) -> Option<Vec<(usize, f64)>> {
+    let d = active.len();
+    let standardized = |row: usize| {
+        active
+            .iter()
+            .map(move |(column, mean, std)| (columns.columns[*column][row] - mean) / std)
+    };
+    let mut sums = [vec![0.; d], vec![0.; d]];
+    let mut lens = [0usize; 2];
+    for row in rows {
+        let group = usize::from(columns.labels[*row] == class);
+        lens[group] += 1;
+        for (sum, x) in sums[group].iter_mut().zip(standardized(*row)) {
+            *sum += x;
+        }
+    }
+    if lens.contains(&0) {
+        return None;
+    }
+    let means = [0, 1].map(|group| {
+        sums[group]
+            .iter()
+            .map(|sum| sum / lens[group] as f64)
+            .collect::<Vec<_>>()
+    });
+    let mut scatter = vec![vec![0.; d]; d];
+    for row in rows {
+        let group = usize::from(columns.labels[*row] == class);
+        let centered: Vec<f64> = standardized(*row)
+            .zip(&means[group])
+            .map(|(x, mean)| x - mean)
+            .collect();
+        for i in 0..d {
+            for j in 0..d {
+                scatter[i][j] += centered[i] * centered[j];
+            }
+        }
+    }
+    for (i, row) in scatter.iter_mut().enumerate() {
+        for value in row.iter_mut() {
+            *value /= rows.len() as f64;
+        }
+        row[i] += RIDGE;
+    }
+    let difference = means[1].iter().zip(&means[0]).map(|(a, b)| a - b).collect();
+    let direction = solve(scatter, difference)?;
+    let weights: Vec<(usize, f64)> = active
+        .iter()
+        .zip(direction)
+        .map(|((column, _mean, std), w)| (*column, w / std))
+        .filter(|(_column, w)| *w != 0.)
+        .collect();
+    let largest = weights
+        .iter()
+        .map(|(_column, w)| w.abs())
+        .fold(0., f64::max);
+    (largest > 0. && largest.is_finite()).then(|| {
+        weights
+            .into_iter()
+            .map(|(column, w)| (column, w / largest))
+            .collect()
+    })
+}
+
+// the best (impurity, hyperplane) over the discriminant of every class at
+// the node, thresholds are tried like `sweep_splits` does on the projections
+pub(crate) fn find_hyperplane(
+    columns: &Columns,
+    indices: &[usize],
+    hyperparameters: &Hyperparameters,
+) -> Option<(f64, Hyperplane)> {
+    // rows with a missing value go right like they do on an axis, but are
+    // left out when fitting
+    let rows: Vec<usize> = indices
+        .iter()
+        .copied()
+        .filter(|row| columns.columns.iter().all(|column| !column[*row].is_nan()))
+        .collect();
+    if rows.len() < 2 {
+        return None;
+    }
+    // (column, mean, std) of the axes that vary at the node
+    let active: Vec<(usize, f64, f64)> = (0..columns.axises.len())
+        .filter_map(|column| {
+            let len = rows.len() as f64;
+            let mean = rows
+                .iter()
+                .map(|row| columns.columns[column][*row])
+                .sum::<f64>()
+                / len;
+            let variance = rows
+                .iter()
+                .map(|row| (columns.columns[column][*row] - mean).powi(2))
+                .sum::<f64>()
+                / len;
+            (variance > 0.).then(|| (column, mean, variance.sqrt()))
+        })
+        .collect();
+    // a single axis is already covered by the exact search
+    if active.len() < 2 {
+        return None;
+    }
+    let counts = columns.counts(indices);
+    let mut best: Option<(f64, Hyperplane)> = None;
+    for (class, count) in counts.iter().enumerate() {
+        // with two classes both discriminants are the same line
+        if *count == 0 || (counts.len() == 2 && class == 1) {
+            continue;
+        }
+        let Some(weights) = discriminant(columns, &rows, &active, class) else {
+            continue;
+        };
+        let values = indices
+            .iter()
+            .map(|row| (columns.project(*row, &weights), columns.labels[*row]))
+            .collect();
+        sweep_values(
+            columns,
+            values,
+            indices,
+            hyperparameters,
+            |split, impurity| {
+                if best
+                    .as_ref()
+                    .is_none_or(|(min_impurity, _)| impurity < *min_impurity)
+                {
+                    best = Some((
+                        impurity,
+                        Hyperplane {
+                            weights: weights
+                                .iter()
+                                .map(|(column, w)| (columns.axises[*column], *w))
+                                .collect(),
+                            split,
+                        },
+                    ));
+                }
+            },
+        );
+    }
+    best
+}
@@ -0,0 +1,532 @@
+use std::{error::Error, fmt};
+
+use crate::dataset::{duplicate, DataSet, LoadError};
+use crate::eval::mean_std;
+use crate::tree::DataPoint;
+
+// a column is numeric when every non-empty value parses as a number, empty
+// values are missing (NaN)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    Numeric(Vec<f64>),
+    Categorical(Vec<String>),
+}
+
+// the columns of a CSV file before they are turned into a `DataSet`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Frame {
+    pub names: Vec<String>,
+    pub columns: Vec<Column>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PreprocessError {
+    MissingColumn(String),
+    DuplicateColumn(String),
+    NoColumn,
+    NotNumeric(String),
+    NotPositive(String),
+}
+
+impl fmt::Display for PreprocessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PreprocessError::MissingColumn(column) => write!(f, "missing column {column:?}"),
+            PreprocessError::DuplicateColumn(column) => {
+                write!(f, "column {column:?} appears twice")
+            }
+            PreprocessError::NoColumn => write!(f, "no feature column is left"),
+            PreprocessError::NotNumeric(column) => {
+                write!(f, "column {column:?} is not numeric, drop or encode it")
+            }
+            PreprocessError::NotPositive(column) => {
+                write!(f, "Box-Cox needs positive values, {column:?} has others")
+            }
+        }
+    }
+}
+
+impl Error for PreprocessError {}
+
+fn parse(csv: &str, labelled: bool) -> Result<(Frame, Vec<String>), LoadError> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .from_reader(csv.as_bytes());
+    let mut names: Vec<String> = rdr.headers()?.iter().map(str::to_owned).collect();
+    if labelled {
+        if names.len() < 2 {
+            return Err(LoadError::NoAxis);
+        }
+        names.pop().unwrap();
+    }
+    if let Some(name) = duplicate(&names) {
+        return Err(LoadError::DuplicateColumn(name.clone()));
+    }
+    let mut values = vec![Vec::new(); names.len()];
+    let mut labels = Vec::new();
+    for record in rdr.records() {
+        let record = record?;
+        let mut fields = record.iter().map(str::trim);
+        for column in values.iter_mut() {
+            column.push(fields.next().unwrap_or_default().to_owned());
+        }
+        if labelled {
+            labels.push(
+                record
+                    .iter()
+                    .next_back()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_owned(),
+            );
+        }
+    }
+    let columns = values
+        .into_iter()
+        .map(|values| {
+            let numeric: Option<Vec<f64>> = values
+                .iter()
+                .map(|value| match value.as_str() {
+                    "" => Some(f64::NAN),
+                    value => value.parse().ok(),
+                })
+                .collect();
+            match numeric {
+                Some(numeric) => Column::Numeric(numeric),
+                None => Column::Categorical(values),
+            }
+        })
+        .collect();
+    Ok((Frame { names, columns }, labels))
+}
+
+// every column is a feature
+pub fn load_frame(csv: &str) -> Result<Frame, LoadError> {
+    Ok(parse(csv, false)?.0)
+}
+
+// the last column is the class label, like `load_dataset`
+pub fn load_labelled_frame(csv: &str) -> Result<(Frame, Vec<String>), LoadError> {
+    parse(csv, true)
+}
+
+impl Frame {
+    pub fn len(&self) -> usize {
+        match self.columns.first() {
+            Some(Column::Numeric(values)) => values.len(),
+            Some(Column::Categorical(values)) => values.len(),
+            None => 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn position(&self, name: &str) -> Result<usize, PreprocessError> {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .ok_or_else(|| PreprocessError::MissingColumn(name.to_owned()))
+    }
+
+    pub fn numeric(&self, name: &str) -> Result<&[f64], PreprocessError> {
+        match &self.columns[self.position(name)?] {
+            Column::Numeric(values) => Ok(values),
+            Column::Categorical(_) => Err(PreprocessError::NotNumeric(name.to_owned())),
+        }
+    }
+
+    // numeric values are written like `f64`'s `Display`, so "1" stays "1"
+    fn categories(&self, name: &str) -> Result<Vec<String>, PreprocessError> {
+        Ok(match &self.columns[self.position(name)?] {
+            Column::Numeric(values) => values.iter().map(f64::to_string).collect(),
+            Column::Categorical(values) => values.clone(),
+        })
+    }
+
+    fn map_numeric(&mut self, name: &str, f: impl Fn(f64) -> f64) -> Result<(), PreprocessError> {
+        let values = self.numeric(name)?.iter().map(|x| f(*x)).collect();
+        let i = self.position(name)?;
+        self.columns[i] = Column::Numeric(values);
+        Ok(())
+    }
+
+    // replaces a column of the same name
+    pub fn push(&mut self, name: String, column: Column) {
+        match self.names.iter().position(|n| *n == name) {
+            Some(i) => self.columns[i] = column,
+            None => {
+                self.names.push(name);
+                self.columns.push(column);
+            }
+        }
+    }
+
+    // the given rows, in that order
+    pub fn select(&self, rows: &[usize]) -> Frame {
+        let columns = self
+            .columns
+            .iter()
+            .map(|column| match column {
+                Column::Numeric(values) => {
+                    Column::Numeric(rows.iter().map(|i| values[*i]).collect())
+                }
+                Column::Categorical(values) => {
+                    Column::Categorical(rows.iter().map(|i| values[*i].clone()).collect())
+                }
+            })
+            .collect();
+        Frame {
+            names: self.names.clone(),
+            columns,
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Column> {
+        let i = self.names.iter().position(|n| n == name)?;
+        self.names.remove(i);
+        Some(self.columns.remove(i))
+    }
+
+    // every column becomes an axis, there has to be one to know the rows
+    pub fn into_dataset(self, labels: Vec<String>) -> Result<DataSet, PreprocessError> {
+        if self.names.is_empty() {
+            return Err(PreprocessError::NoColumn);
+        }
+        if let Some(name) = duplicate(&self.names) {
+            return Err(PreprocessError::DuplicateColumn(name.clone()));
+        }
+        let points = self.points(&self.names)?;
+        let rows = points
+            .into_iter()
+            .map(|DataPoint(vec)| vec)
+            .zip(labels)
+            .collect();
+        // the names are unique, checked above
+        Ok(DataSet::from_rows(self.names, rows).unwrap())
+    }
+
+    // the values of the named columns, in that order, row by row
+    pub fn points(&self, axises: &[String]) -> Result<Vec<DataPoint>, PreprocessError> {
+        let columns = axises
+            .iter()
+            .map(|axis| self.numeric(axis))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((0..self.len())
+            .map(|row| DataPoint(columns.iter().map(|column| column[row]).collect()))
+            .collect())
+    }
+}
+
+// what to do, `Pipeline::fit` turns it into a `Step` with the parameters
+// learned from the training data
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transform {
+    Drop(Vec<String>),
+    // ln(1 + x)
+    Log(String),
+    // λ is estimated by maximum likelihood when not given
+    BoxCox {
+        column: String,
+        lambda: Option<f64>,
+    },
+    Standardize(String),
+    // adds "numerator/denominator"
+    Ratio(String, String),
+    // adds "left*right"
+    Product(String, String),
+    // replaces the column with one 0/1 column "column=category" per category
+    OneHot(String),
+    // numbers the categories in the given order, or in order of first appearance
+    Ordinal {
+        column: String,
+        categories: Option<Vec<String>>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Step {
+    Drop {
+        columns: Vec<String>,
+    },
+    Log {
+        column: String,
+    },
+    BoxCox {
+        column: String,
+        lambda: f64,
+    },
+    Standardize {
+        column: String,
+        mean: f64,
+        std: f64,
+    },
+    Ratio {
+        numerator: String,
+        denominator: String,
+    },
+    Product {
+        left: String,
+        right: String,
+    },
+    OneHot {
+        column: String,
+        categories: Vec<String>,
+    },
+    Ordinal {
+        column: String,
+        categories: Vec<String>,
+    },
+}
+
+fn box_cox(x: f64, lambda: f64) -> f64 {
+    if x <= 0. {
+        f64::NAN
+    } else if lambda.abs() < 1e-12 {
+        x.ln()
+    } else {
+        (x.powf(lambda) - 1.) / lambda
+    }
+}
+
+// the λ in [-2, 2], to two decimals, maximizing the profile log-likelihood
+fn box_cox_lambda(values: &[f64]) -> f64 {
+    let log_sum: f64 = values.iter().map(|x| x.ln()).sum();
+    let log_likelihood = |lambda: f64| {
+        let transformed: Vec<f64> = values.iter().map(|x| box_cox(*x, lambda)).collect();
+        let (_mean, std) = mean_std(&transformed);
+        -(values.len() as f64) * std.ln() + (lambda - 1.) * log_sum
+    };
+    (-200..=200)
+        .map(|i| i as f64 / 100.)
+        .max_by(|a, b| log_likelihood(*a).total_cmp(&log_likelihood(*b)))
+        .unwrap()
+}
+
+fn first_appearance(values: &[String]) -> Vec<String> {
+    let mut categories = Vec::<String>::new();
+    for value in values {
+        if !categories.contains(value) {
+            categories.push(value.clone());
+        }
+    }
+    categories
+}
+
+fn present(values: &[f64]) -> Vec<f64> {
+    values.iter().copied().filter(|x| !x.is_nan()).collect()
+}
+
+impl Step {
+    fn fit(transform: &Transform, frame: &Frame) -> Result<Step, PreprocessError> {
+        Ok(match transform.clone() {
+            Transform::Drop(columns) => Step::Drop { columns },
+            Transform::Log(column) => Step::Log { column },
+            Transform::BoxCox { column, lambda } => {
+                let values = present(frame.numeric(&column)?);
+                if values.iter().any(|x| *x <= 0.) {
+                    return Err(PreprocessError::NotPositive(column));
+                }
+                let lambda = match lambda {
+                    Some(lambda) => lambda,
+                    None if values.len() < 2 => 1.,
+                    None => box_cox_lambda(&values),
+                };
+                Step::BoxCox { column, lambda }
+            }
+            Transform::Standardize(column) => {
+                let values = present(frame.numeric(&column)?);
+                let (mean, std) = if values.is_empty() {
+                    (0., 1.)
+                } else {
+                    mean_std(&values)
+                };
+                Step::Standardize {
+                    column,
+                    mean,
+                    std: if std > 0. { std } else { 1. },
+                }
+            }
+            Transform::Ratio(numerator, denominator) => Step::Ratio {
+                numerator,
+                denominator,
+            },
+            Transform::Product(left, right) => Step::Product { left, right },
+            Transform::OneHot(column) => Step::OneHot {
+                categories: first_appearance(&frame.categories(&column)?),
+                column,
+            },
+            Transform::Ordinal { column, categories } => Step::Ordinal {
+                categories: match categories {
+                    Some(categories) => categories,
+                    None => first_appearance(&frame.categories(&column)?),
+                },
+                column,
+            },
+        })
+    }
+
+    // values outside the domain of a transform and categories not seen while
+    // fitting become NaN, or all zeros for one-hot
+    pub fn apply(&self, frame: &mut Frame) -> Result<(), PreprocessError> {
+        match self {
+            Step::Drop { columns } => {
+                // a column missing at prediction time is as good as dropped
+                for column in columns {
+                    frame.remove(column);
+                }
+            }
+            Step::Log { column } => {
+                frame.map_numeric(column, |x| if x > -1. { x.ln_1p() } else { f64::NAN })?
+            }
+            Step::BoxCox { column, lambda } => {
+                frame.map_numeric(column, |x| box_cox(x, *lambda))?
+            }
+            Step::Standardize { column, mean, std } => {
+                frame.map_numeric(column, |x| (x - mean) / std)?
+            }
+            Step::Ratio {
+                numerator,
+                denominator,
+            } => {
+                let values = frame
+                    .numeric(numerator)?
+                    .iter()
+                    .zip(frame.numeric(denominator)?)
+                    .map(|(n, d)| if *d == 0. { f64::NAN } else { n / d })
+                    .collect();
+                frame.push(
+                    format!("{numerator}/{denominator}"),
+                    Column::Numeric(values),
+                );
+            }
+            Step::Product { left, right } => {
+                let values = frame
+                    .numeric(left)?
+                    .iter()
+                    .zip(frame.numeric(right)?)
+                    .map(|(l, r)| l * r)
+                    .collect();
+                frame.push(format!("{left}*{right}"), Column::Numeric(values));
+            }
+            Step::OneHot { column, categories } => {
+                let values = frame.categories(column)?;
+                frame.remove(column);
+                for category in categories {
+                    let indicator = values
+                        .iter()
+                        .map(|value| if value == category { 1. } else { 0. })
+                        .collect();
+                    frame.push(format!("{column}={category}"), Column::Numeric(indicator));
+                }
+            }
+            Step::Ordinal { column, categories } => {
+                let values = frame
+                    .categories(column)?
+                    .iter()
+                    .map(|value| {
+                        categories
+                            .iter()
+                            .position(|category| category == value)
+                            .map_or(f64::NAN, |i| i as f64)
+                    })
+                    .collect();
+                frame.push(column.clone(), Column::Numeric(values));
+            }
+        }
+        Ok(())
+    }
+}
+
+// the fitted steps, applied in order, saved with a model so predictions see
+// the same features as training
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pipeline {
+    pub steps: Vec<Step>,
+}
+
+impl Pipeline {
+    // every transform is fitted on the output of the ones before it, returns
+    // the transformed frame as well
+    pub fn fit(
+        transforms: &[Transform],
+        mut frame: Frame,
+    ) -> Result<(Pipeline, Frame), PreprocessError> {
+        let mut steps = Vec::new();
+        for transform in transforms {
+            let step = Step::fit(transform, &frame)?;
+            step.apply(&mut frame)?;
+            steps.push(step);
+        }
+        Ok((Pipeline { steps }, frame))
+    }
+
+    pub fn apply(&self, mut frame: Frame) -> Result<Frame, PreprocessError> {
+        for step in &self.steps {
+            step.apply(&mut frame)?;
+        }
+        Ok(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fits_and_reapplies_a_pipeline() {
+        let (frame, labels) = load_labelled_frame(
+            "id,width,height,color,class\n1,1,1,red,a\n2,2,3,blue,a\n3,3,1,red,b\n4,,3,green,b\n",
+        )
+        .unwrap();
+        let transforms = [
+            Transform::Drop(vec!["id".to_owned()]),
+            Transform::Ratio("width".to_owned(), "height".to_owned()),
+            Transform::Standardize("height".to_owned()),
+            Transform::OneHot("color".to_owned()),
+        ];
+        let (pipeline, frame) = Pipeline::fit(&transforms, frame).unwrap();
+        assert_eq!(
+            frame.names,
+            [
+                "width",
+                "height",
+                "width/height",
+                "color=red",
+                "color=blue",
+                "color=green"
+            ]
+        );
+        let dataset = frame.into_dataset(labels).unwrap();
+        assert_eq!(dataset.rows[0].0[2], 1.);
+        assert_eq!(dataset.rows[1].0[1], 1.);
+        assert!(dataset.rows[3].0[0].is_nan());
+        assert_eq!(dataset.classes, ["a", "b"]);
+
+        // reordered columns and an unseen category
+        let points = pipeline
+            .apply(load_frame("color,height,width\nyellow,4,1\n").unwrap())
+            .unwrap()
+            .points(&dataset.axises)
+            .unwrap();
+        assert_eq!(points[0].0, [1., 2., 0.25, 0., 0., 0.]);
+
+        let (frame, labels) = load_labelled_frame("a,b,class\n1,2,x\n").unwrap();
+        let (_pipeline, frame) = Pipeline::fit(
+            &[Transform::Drop(vec!["a".to_owned(), "b".to_owned()])],
+            frame,
+        )
+        .unwrap();
+        assert_eq!(
+            frame.into_dataset(labels).unwrap_err(),
+            PreprocessError::NoColumn
+        );
+        assert!(matches!(
+            load_labelled_frame("a,a,class\n1,2,x\n"),
+            Err(LoadError::DuplicateColumn(column)) if column == "a"
+        ));
+    }
+}
@@ -0,0 +1,461 @@
+use std::collections::VecDeque;
+
+use crate::dataset::DataSet;
+use crate::i18n::Locale;
+use crate::tree::{Axis, Class, Criterion, DataPoint, DecisionTree, Hyperplane, Multiway};
+
+// lower <= dp[axis] < upper, tests on the same axis along a path are merged
+// into one interval so only the tightest bounds are kept
+#[derive(Debug, Clone, Copy)]
+pub struct Condition {
+    pub axis: Axis,
+    pub lower: Option<f64>,
+    pub upper: Option<f64>,
+}
+
+impl Condition {
+    // missing values go right at every split, so they pass lower bounds only
+    pub fn matches(&self, dp: &DataPoint) -> bool {
+        let value = dp[self.axis];
+        self.lower
+            .is_none_or(|lower| value >= lower || value.is_nan())
+            && self.upper.is_none_or(|upper| value < upper)
+    }
+}
+
+// the side of an oblique split a path takes, w·x < split when `below`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HalfSpace {
+    pub hyperplane: Hyperplane,
+    pub below: bool,
+}
+
+impl HalfSpace {
+    pub fn matches(&self, dp: &DataPoint) -> bool {
+        (self.hyperplane.value(dp) < self.hyperplane.split) == self.below
+    }
+}
+
+fn with_half_space(
+    half_spaces: &[HalfSpace],
+    hyperplane: &Hyperplane,
+    below: bool,
+) -> Vec<HalfSpace> {
+    let mut half_spaces = half_spaces.to_vec();
+    half_spaces.push(HalfSpace {
+        hyperplane: hyperplane.clone(),
+        below,
+    });
+    half_spaces
+}
+
+// `conditions` with the interval on `axis` intersected with [lower, upper)
+pub(crate) fn narrow(
+    conditions: &[Condition],
+    axis: Axis,
+    lower: Option<f64>,
+    upper: Option<f64>,
+) -> Vec<Condition> {
+    let mut conditions = conditions.to_vec();
+    let index = match conditions.iter().position(|c| c.axis == axis) {
+        Some(index) => index,
+        None => {
+            conditions.push(Condition {
+                axis,
+                lower: None,
+                upper: None,
+            });
+            conditions.len() - 1
+        }
+    };
+    let c = &mut conditions[index];
+    if let Some(lower) = lower {
+        c.lower = Some(c.lower.map_or(lower, |l| l.max(lower)));
+    }
+    if let Some(upper) = upper {
+        c.upper = Some(c.upper.map_or(upper, |u| u.min(upper)));
+    }
+    conditions
+}
+
+#[derive(Debug, Clone)]
+pub struct Region {
+    pub depth: usize,
+    pub conditions: Vec<Condition>,
+    // oblique splits on the path from the root
+    pub half_spaces: Vec<HalfSpace>,
+    // all `None` for a leaf
+    pub criterion: Option<Criterion>,
+    pub hyperplane: Option<Hyperplane>,
+    pub multiway: Option<Multiway>,
+}
+
+impl Region {
+    pub fn matches(&self, dp: &DataPoint) -> bool {
+        self.conditions.iter().all(|c| c.matches(dp))
+            && self.half_spaces.iter().all(|h| h.matches(dp))
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.criterion.is_none() && self.hyperplane.is_none() && self.multiway.is_none()
+    }
+}
+
+// every node of `tree` in breadth-first order, so the index of a region is
+// the node number used by `growth::Step`
+pub fn regions(tree: &DecisionTree) -> Vec<Region> {
+    let mut regions = Vec::new();
+    let mut queue = VecDeque::from([(tree, 0, Vec::new(), Vec::new())]);
+    while let Some((tree, depth, conditions, half_spaces)) = queue.pop_front() {
+        let (criterion, hyperplane, multiway) = match tree {
+            DecisionTree::Branch(c, left_tree, right_tree) => {
+                queue.push_back((
+                    left_tree,
+                    depth + 1,
+                    narrow(&conditions, c.axis, None, Some(c.split)),
+                    half_spaces.clone(),
+                ));
+                queue.push_back((
+                    right_tree,
+                    depth + 1,
+                    narrow(&conditions, c.axis, Some(c.split), None),
+                    half_spaces.clone(),
+                ));
+                (Some(*c), None, None)
+            }
+            DecisionTree::Oblique(hyperplane, left_tree, right_tree) => {
+                queue.push_back((
+                    left_tree,
+                    depth + 1,
+                    conditions.clone(),
+                    with_half_space(&half_spaces, hyperplane, true),
+                ));
+                queue.push_back((
+                    right_tree,
+                    depth + 1,
+                    conditions.clone(),
+                    with_half_space(&half_spaces, hyperplane, false),
+                ));
+                (None, Some(hyperplane.clone()), None)
+            }
+            DecisionTree::Multiway(multiway, subtrees) => {
+                for (i, subtree) in subtrees.iter().enumerate() {
+                    let (lower, upper) = multiway.interval(i);
+                    queue.push_back((
+                        subtree,
+                        depth + 1,
+                        narrow(&conditions, multiway.axis, lower, upper),
+                        half_spaces.clone(),
+                    ));
+                }
+                (None, None, Some(multiway.clone()))
+            }
+            DecisionTree::Leave(_) => (None, None, None),
+        };
+        regions.push(Region {
+            depth,
+            conditions,
+            half_spaces,
+            criterion,
+            hyperplane,
+            multiway,
+        });
+    }
+    regions
+}
+
+// the index in `regions(tree)` of the leaf `dp` falls in, taking the same
+// branches as `predict`
+pub fn leaf_index(tree: &DecisionTree, dp: &DataPoint) -> usize {
+    let mut queue = VecDeque::from([(tree, true)]);
+    let mut index = 0;
+    while let Some((tree, on_path)) = queue.pop_front() {
+        let (children, taken): (Vec<&DecisionTree>, usize) = match tree {
+            DecisionTree::Branch(c, left, right) => {
+                (vec![left, right], if dp[c.axis] < c.split { 0 } else { 1 })
+            }
+            DecisionTree::Oblique(hyperplane, left, right) => (
+                vec![left, right],
+                if hyperplane.value(dp) < hyperplane.split {
+                    0
+                } else {
+                    1
+                },
+            ),
+            DecisionTree::Multiway(multiway, subtrees) => {
+                (subtrees.iter().collect(), multiway.child(dp[multiway.axis]))
+            }
+            DecisionTree::Leave(_) if on_path => return index,
+            DecisionTree::Leave(_) => (Vec::new(), 0),
+        };
+        for (i, child) in children.into_iter().enumerate() {
+            queue.push_back((child, on_path && i == taken));
+        }
+        index += 1;
+    }
+    unreachable!("every path ends in a leaf")
+}
+
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub conditions: Vec<Condition>,
+    pub half_spaces: Vec<HalfSpace>,
+    pub class: Class,
+    pub coverage: usize,
+    pub support: f64,
+    pub confidence: f64,
+}
+
+impl Rule {
+    pub fn matches(&self, dp: &DataPoint) -> bool {
+        self.conditions.iter().all(|c| c.matches(dp))
+            && self.half_spaces.iter().all(|h| h.matches(dp))
+    }
+}
+
+fn collect_paths(
+    tree: &DecisionTree,
+    path: &mut Vec<Condition>,
+    half_spaces: &mut Vec<HalfSpace>,
+    rules: &mut Vec<Rule>,
+) {
+    match tree {
+        DecisionTree::Branch(c, left_tree, right_tree) => {
+            let position = path.iter().position(|cond| cond.axis == c.axis);
+            let index = position.unwrap_or_else(|| {
+                path.push(Condition {
+                    axis: c.axis,
+                    lower: None,
+                    upper: None,
+                });
+                path.len() - 1
+            });
+            let saved = path[index];
+
+            path[index].upper = Some(saved.upper.map_or(c.split, |upper| upper.min(c.split)));
+            collect_paths(left_tree, path, half_spaces, rules);
+            path[index] = saved;
+
+            path[index].lower = Some(saved.lower.map_or(c.split, |lower| lower.max(c.split)));
+            collect_paths(right_tree, path, half_spaces, rules);
+            path[index] = saved;
+
+            if position.is_none() {
+                path.pop();
+            }
+        }
+        DecisionTree::Oblique(hyperplane, left_tree, right_tree) => {
+            for (subtree, below) in [(left_tree, true), (right_tree, false)] {
+                half_spaces.push(HalfSpace {
+                    hyperplane: hyperplane.clone(),
+                    below,
+                });
+                collect_paths(subtree, path, half_spaces, rules);
+                half_spaces.pop();
+            }
+        }
+        DecisionTree::Multiway(multiway, subtrees) => {
+            for (i, subtree) in subtrees.iter().enumerate() {
+                let (lower, upper) = multiway.interval(i);
+                let mut conditions = narrow(path, multiway.axis, lower, upper);
+                collect_paths(subtree, &mut conditions, half_spaces, rules);
+            }
+        }
+        DecisionTree::Leave(class) => rules.push(Rule {
+            conditions: path.clone(),
+            half_spaces: half_spaces.clone(),
+            class: *class,
+            coverage: 0,
+            support: 0.,
+            confidence: 0.,
+        }),
+    }
+}
+
+pub fn extract_rules(tree: &DecisionTree, data: &[(DataPoint, Class)]) -> Vec<Rule> {
+    let mut rules = Vec::new();
+    collect_paths(tree, &mut Vec::new(), &mut Vec::new(), &mut rules);
+    for rule in rules.iter_mut() {
+        let mut correct = 0;
+        for (dp, class) in data {
+            if rule.matches(dp) {
+                rule.coverage += 1;
+                if *class == rule.class {
+                    correct += 1;
+                }
+            }
+        }
+        if !data.is_empty() {
+            rule.support = rule.coverage as f64 / data.len() as f64;
+        }
+        if rule.coverage > 0 {
+            rule.confidence = correct as f64 / rule.coverage as f64;
+        }
+    }
+    rules
+}
+
+// four significant digits, weights of axes on different scales can be far apart
+fn display_weight(w: f64) -> String {
+    let digits = 3 - w.abs().log10().floor().min(3.) as i32;
+    format!("{:.*}", digits.max(0) as usize, w.abs())
+}
+
+// w·x with the terms written out, e.g. "1.000·a - 0.02500·b"
+pub fn display_combination(dataset: &DataSet, hyperplane: &Hyperplane) -> String {
+    let mut text = String::new();
+    for (i, (axis, w)) in hyperplane.weights.iter().enumerate() {
+        let name = dataset.axis_map.get_by_right(axis).unwrap();
+        let sign = match (i, *w < 0.) {
+            (0, true) => "-",
+            (0, false) => "",
+            (_, true) => " - ",
+            (_, false) => " + ",
+        };
+        text += &format!("{sign}{}·{name}", display_weight(*w));
+    }
+    text
+}
+
+pub fn display_hyperplane(dataset: &DataSet, hyperplane: &Hyperplane) -> String {
+    format!(
+        "{} < {:.3}",
+        display_combination(dataset, hyperplane),
+        hyperplane.split
+    )
+}
+
+pub fn display_conditions(
+    dataset: &DataSet,
+    conditions: &[Condition],
+    half_spaces: &[HalfSpace],
+    locale: Locale,
+) -> String {
+    if conditions.is_empty() && half_spaces.is_empty() {
+        return locale.pick("(全部)", "(all)").to_owned();
+    }
+    conditions
+        .iter()
+        .map(|c| {
+            let name = dataset.axis_map.get_by_right(&c.axis).unwrap();
+            match (c.lower, c.upper) {
+                (Some(lower), Some(upper)) => format!("{lower:.3} <= {name} < {upper:.3}"),
+                (Some(lower), None) => format!("{name} >= {lower:.3}"),
+                (None, Some(upper)) => format!("{name} < {upper:.3}"),
+                (None, None) => unreachable!(),
+            }
+        })
+        .chain(half_spaces.iter().map(|h| {
+            let combination = display_combination(dataset, &h.hyperplane);
+            let relation = if h.below { "<" } else { ">=" };
+            format!("{combination} {relation} {:.3}", h.hyperplane.split)
+        }))
+        .collect::<Vec<_>>()
+        .join(locale.pick(" 且 ", " and "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::{load_csv, load_dataset};
+    use crate::tree::{predict, train, Hyperparameters, LabelledData};
+
+    fn branch(axis: i32, split: f64, left: DecisionTree, right: DecisionTree) -> DecisionTree {
+        let c = Criterion {
+            axis: Axis(axis),
+            split,
+            p_value: None,
+        };
+        DecisionTree::Branch(c, Box::new(left), Box::new(right))
+    }
+
+    #[test]
+    fn merges_thresholds_and_scores_every_leaf() {
+        // x < 5 then x < 3 then y < 1 on the left, x >= 5 on the right
+        let tree = branch(
+            0,
+            5.,
+            branch(
+                0,
+                3.,
+                DecisionTree::Leave(Class(0)),
+                branch(
+                    1,
+                    1.,
+                    DecisionTree::Leave(Class(1)),
+                    DecisionTree::Leave(Class(0)),
+                ),
+            ),
+            DecisionTree::Leave(Class(1)),
+        );
+        let data: LabelledData = [
+            ([1., 0.], 0),
+            ([2., 2.], 1),
+            ([4., 0.], 1),
+            ([4., 0.5], 0),
+            ([4., 2.], 0),
+            ([6., 0.], 1),
+            ([7., 3.], 1),
+            ([8., 3.], 0),
+        ]
+        .into_iter()
+        .map(|(dp, class)| (DataPoint(dp.to_vec()), Class(class)))
+        .collect();
+        let rules = extract_rules(&tree, &data);
+        let bounds = |rule: &Rule| -> Vec<(i32, Option<f64>, Option<f64>)> {
+            rule.conditions
+                .iter()
+                .map(|c| (c.axis.0, c.lower, c.upper))
+                .collect()
+        };
+        assert_eq!(rules.len(), 4);
+        assert_eq!(bounds(&rules[0]), [(0, None, Some(3.))]);
+        assert_eq!(
+            bounds(&rules[1]),
+            [(0, Some(3.), Some(5.)), (1, None, Some(1.))]
+        );
+        assert_eq!(
+            bounds(&rules[2]),
+            [(0, Some(3.), Some(5.)), (1, Some(1.), None)]
+        );
+        assert_eq!(bounds(&rules[3]), [(0, Some(5.), None)]);
+        let scores: Vec<(usize, f64, f64)> = rules
+            .iter()
+            .map(|rule| (rule.coverage, rule.support, rule.confidence))
+            .collect();
+        assert_eq!(
+            scores,
+            [
+                (2, 0.25, 0.5),
+                (2, 0.25, 0.5),
+                (1, 0.125, 1.),
+                (3, 0.375, 2. / 3.)
+            ]
+        );
+        // every point falls in exactly one leaf
+        let coverage: usize = rules.iter().map(|rule| rule.coverage).sum();
+        assert_eq!(coverage, data.len());
+    }
+
+    #[test]
+    fn missing_values_follow_predict() {
+        let dataset = load_dataset(load_csv("iris".to_owned())).unwrap();
+        let data = dataset.labelled();
+        let tree = train(
+            &dataset.mapped_axis,
+            &dataset.mapped_class,
+            data.clone(),
+            &Hyperparameters::default(),
+        );
+        let regions = regions(&tree);
+        let rules = extract_rules(&tree, &data);
+        for (mut dp, _class) in data {
+            dp.0[2] = f64::NAN;
+            let leaf = &regions[leaf_index(&tree, &dp)];
+            assert!(leaf.is_leaf() && leaf.matches(&dp));
+            let matching: Vec<&Rule> = rules.iter().filter(|rule| rule.matches(&dp)).collect();
+            assert_eq!(matching.len(), 1);
+            assert_eq!(matching[0].class, predict(&tree, &dp));
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::eval::{cross_validate, mean_std};
+use crate::tree::{train, Axis, Class, DataPoint, DecisionTree, Hyperparameters, Impurity};
+
+// the values tried for each setting, the others are taken from a base
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Grid {
+    pub max_depth: Vec<usize>,
+    pub min_elements_per_leaf: Vec<usize>,
+    pub impurity: Vec<Impurity>,
+    pub ccp_alpha: Vec<f64>,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self {
+            max_depth: vec![3, 5, 7, 10],
+            min_elements_per_leaf: vec![1, 5, 10],
+            impurity: vec![Impurity::Gini, Impurity::Entropy],
+            ccp_alpha: vec![0., 0.001, 0.005, 0.01],
+        }
+    }
+}
+
+impl Grid {
+    // every combination, the last setting varying fastest
+    pub fn combinations(&self, base: &Hyperparameters) -> Vec<Hyperparameters> {
+        let mut combinations = Vec::new();
+        for max_depth in &self.max_depth {
+            for min_elements_per_leaf in &self.min_elements_per_leaf {
+                for impurity in &self.impurity {
+                    for ccp_alpha in &self.ccp_alpha {
+                        combinations.push(Hyperparameters {
+                            max_depth: *max_depth,
+                            min_elements_per_leaf: *min_elements_per_leaf,
+                            impurity: *impurity,
+                            ccp_alpha: *ccp_alpha,
+                            ..*base
+                        });
+                    }
+                }
+            }
+        }
+        combinations
+    }
+
+    // `n` distinct combinations picked at random, all of them if there are fewer
+    pub fn sample(&self, base: &Hyperparameters, n: usize, seed: u64) -> Vec<Hyperparameters> {
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+        self.combinations(base)
+            .choose_multiple(&mut rng, n)
+            .copied()
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchResult {
+    pub hyperparameters: Hyperparameters,
+    // accuracy on each fold
+    pub scores: Vec<f64>,
+    pub mean: f64,
+    pub std: f64,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchReport {
+    // best first
+    pub results: Vec<SearchResult>,
+    // the best hyperparameters refitted on all of the data
+    pub tree: DecisionTree,
+}
+
+// scores every candidate with `score`, its accuracy on each fold; ranked by
+// mean accuracy, then by the lower spread, ties keep the order of `candidates`
+pub fn rank(
+    candidates: &[Hyperparameters],
+    score: impl Fn(&Hyperparameters) -> Vec<f64> + Sync,
+) -> Vec<SearchResult> {
+    let result = |hyperparameters: &Hyperparameters| {
+        let scores = score(hyperparameters);
+        let (mean, std) = mean_std(&scores);
+        SearchResult {
+            hyperparameters: *hyperparameters,
+            scores,
+            mean,
+            std,
+        }
+    };
+    #[cfg(feature = "parallel")]
+    let mut results: Vec<_> = candidates.par_iter().map(result).collect();
+    #[cfg(not(feature = "parallel"))]
+    let mut results: Vec<_> = candidates.iter().map(result).collect();
+    results.sort_by(|a, b| b.mean.total_cmp(&a.mean).then(a.std.total_cmp(&b.std)));
+    results
+}
+
+pub fn search(
+    axises: &[Axis],
+    classes: &[Class],
+    data: &[(DataPoint, Class)],
+    candidates: &[Hyperparameters],
+    k: usize,
+    seed: u64,
+) -> SearchReport {
+    search_with_progress(axises, classes, data, candidates, k, seed, &|_| {})
+}
+
+// ranks every candidate by `k`-fold cross-validation on `data`, all on the
+// same folds; `on_progress` gets the number of candidates scored
+pub fn search_with_progress(
+    axises: &[Axis],
+    classes: &[Class],
+    data: &[(DataPoint, Class)],
+    candidates: &[Hyperparameters],
+    k: usize,
+    seed: u64,
+    on_progress: &(dyn Fn(usize) + Sync),
+) -> SearchReport {
+    let done = AtomicUsize::new(0);
+    let results = rank(candidates, |hyperparameters| {
+        let scores = cross_validate(axises, classes, data, k, seed, hyperparameters);
+        on_progress(done.fetch_add(1, Ordering::Relaxed) + 1);
+        scores
+    });
+    let best = results
+        .first()
+        .map_or_else(Hyperparameters::default, |result| result.hyperparameters);
+    SearchReport {
+        tree: train(axises, classes, data.to_vec(), &best),
+        results,
+    }
+}
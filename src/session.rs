@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use web_sys::{Storage, UrlSearchParams};
+
+use crate::i18n::Locale;
+use crate::tree::{DecisionTree, Hyperparameters};
+
+const STORAGE_KEY: &str = "dectree-session";
+// the datasets `dataset::load_csv` knows, anything else in a URL is ignored
+const SOURCES: [&str; 3] = ["iris", "wine", "rice"];
+
+// what the page shows, saved to localStorage when it is left and restored on load
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub locale: Locale,
+    pub source: Option<String>,
+    pub seed: u64,
+    pub x_axis: Option<usize>,
+    pub y_axis: Option<usize>,
+    // used by the next training
+    pub hyperparameters: Hyperparameters,
+    pub tree: Option<DecisionTree>,
+    // the ones `tree` was grown with
+    pub tree_hyperparameters: Hyperparameters,
+    pub log: String,
+}
+
+fn storage() -> Option<Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+pub fn save(session: &Session) {
+    if let Some(storage) = storage() {
+        // running out of quota only loses the session
+        let _ = storage.set_item(STORAGE_KEY, &serde_json::to_string(session).unwrap());
+    }
+}
+
+pub fn load() -> Option<Session> {
+    let json = storage()?.get_item(STORAGE_KEY).ok()??;
+    serde_json::from_str(&json).ok()
+}
+
+// a run as encoded in a shared URL, training on `source` split with `seed`
+// reproduces the same tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedRun {
+    pub source: String,
+    pub seed: u64,
+    pub hyperparameters: Hyperparameters,
+}
+
+pub fn from_url() -> Option<SharedRun> {
+    let search = web_sys::window()?.location().search().ok()?;
+    let params = UrlSearchParams::new_with_str(&search).ok()?;
+    Some(SharedRun {
+        source: params
+            .get("dataset")
+            .filter(|source| SOURCES.contains(&source.as_str()))?,
+        seed: params
+            .get("seed")
+            .and_then(|seed| seed.parse().ok())
+            .unwrap_or_default(),
+        hyperparameters: params
+            .get("hyperparameters")
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+    })
+}
+
+pub fn to_url(run: &SharedRun) -> String {
+    let location = web_sys::window().unwrap().location();
+    let params = UrlSearchParams::new().unwrap();
+    params.append("dataset", &run.source);
+    params.append("seed", &run.seed.to_string());
+    params.append(
+        "hyperparameters",
+        &serde_json::to_string(&run.hyperparameters).unwrap(),
+    );
+    format!(
+        "{}{}?{}",
+        location.origin().unwrap(),
+        location.pathname().unwrap(),
+        String::from(params.to_string())
+    )
+}
+
+// drops the query so that reloading restores the saved session instead
+pub fn clear_url() {
+    let window = web_sys::window().unwrap();
+    let pathname = window.location().pathname().unwrap();
+    let _ = window.history().unwrap().replace_state_with_url(
+        &wasm_bindgen::JsValue::NULL,
+        "",
+        Some(&pathname),
+    );
+}
@@ -0,0 +1,157 @@
+use crate::dataset::DataSet;
+use crate::eval::mean_std;
+
+// statistics of one axis over the rows where it is not missing (NaN)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisSummary {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    // population standard deviation
+    pub std: f64,
+    // first quartile, median and third quartile
+    pub quartiles: [f64; 3],
+    pub missing: usize,
+}
+
+// the `q` quantile of sorted values, interpolating linearly between neighbours
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+fn present(dataset: &DataSet, axis: usize) -> Vec<f64> {
+    dataset
+        .rows
+        .iter()
+        .map(|(vec, _label)| vec[axis])
+        .filter(|value| !value.is_nan())
+        .collect()
+}
+
+// `None` for an axis with no values at all
+pub fn summarize(dataset: &DataSet, axis: usize) -> Option<AxisSummary> {
+    let mut values = present(dataset, axis);
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let (mean, std) = mean_std(&values);
+    Some(AxisSummary {
+        min: values[0],
+        max: values[values.len() - 1],
+        mean,
+        std,
+        quartiles: [0.25, 0.5, 0.75].map(|q| quantile(&values, q)),
+        missing: dataset.rows.len() - values.len(),
+    })
+}
+
+// number of rows of every class, in the order of `dataset.classes`
+pub fn class_counts(dataset: &DataSet) -> Vec<usize> {
+    dataset
+        .classes
+        .iter()
+        .map(|class| {
+            dataset
+                .rows
+                .iter()
+                .filter(|(_vec, label)| label == class)
+                .count()
+        })
+        .collect()
+}
+
+// equal-width bins over the range of one axis, bin `b` holds the values in
+// [edges[b], edges[b + 1]) and the last one also holds the maximum
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureHistogram {
+    pub edges: Vec<f64>,
+    // counts[class][bin], classes in the order of `dataset.classes`
+    pub counts: Vec<Vec<usize>>,
+}
+
+pub fn feature_histogram(dataset: &DataSet, axis: usize, bins: usize) -> FeatureHistogram {
+    let bins = bins.max(1);
+    let values = present(dataset, axis);
+    let (min, max) = values
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(min, max), value| {
+            (min.min(*value), max.max(*value))
+        });
+    let (min, max) = if values.is_empty() {
+        (0., 0.)
+    } else {
+        (min, max)
+    };
+    let width = (max - min) / bins as f64;
+    let edges = (0..=bins).map(|b| min + width * b as f64).collect();
+    let mut counts = vec![vec![0; bins]; dataset.classes.len()];
+    for (vec, label) in &dataset.rows {
+        if vec[axis].is_nan() {
+            continue;
+        }
+        let class = dataset.classes.iter().position(|c| c == label).unwrap();
+        let bin = if width > 0. {
+            (((vec[axis] - min) / width) as usize).min(bins - 1)
+        } else {
+            0
+        };
+        counts[class][bin] += 1;
+    }
+    FeatureHistogram { edges, counts }
+}
+
+// Pearson correlation of every pair of axes over the rows where both are
+// present, NaN when one of them is constant
+pub fn correlation(dataset: &DataSet) -> Vec<Vec<f64>> {
+    let n = dataset.axises.len();
+    let mut matrix = vec![vec![f64::NAN; n]; n];
+    for a in 0..n {
+        for b in a..n {
+            let pairs: Vec<(f64, f64)> = dataset
+                .rows
+                .iter()
+                .map(|(vec, _label)| (vec[a], vec[b]))
+                .filter(|(x, y)| !x.is_nan() && !y.is_nan())
+                .collect();
+            let len = pairs.len() as f64;
+            let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / len;
+            let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / len;
+            let (mut cov, mut var_x, mut var_y) = (0., 0., 0.);
+            for (x, y) in &pairs {
+                cov += (x - mean_x) * (y - mean_y);
+                var_x += (x - mean_x).powi(2);
+                var_y += (y - mean_y).powi(2);
+            }
+            let r = cov / (var_x * var_y).sqrt();
+            matrix[a][b] = r;
+            matrix[b][a] = r;
+        }
+    }
+    matrix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::load_dataset;
+
+    #[test]
+    fn summarizes_a_small_dataset() {
+        let dataset = load_dataset("x,y,class\n1,2,a\n2,4,a\n3,6,b\n4,NaN,b\n").unwrap();
+        let x = summarize(&dataset, 0).unwrap();
+        assert_eq!((x.min, x.max, x.mean, x.missing), (1., 4., 2.5, 0));
+        assert_eq!(x.quartiles, [1.75, 2.5, 3.25]);
+        let y = summarize(&dataset, 1).unwrap();
+        assert_eq!((y.min, y.max, y.missing), (2., 6., 1));
+        assert_eq!(class_counts(&dataset), vec![2, 2]);
+        let histogram = feature_histogram(&dataset, 0, 3);
+        assert_eq!(histogram.counts, vec![vec![1, 1, 0], vec![0, 0, 2]]);
+        let r = correlation(&dataset);
+        assert!((r[0][1] - 1.).abs() < 1e-12);
+        assert!((r[0][0] - 1.).abs() < 1e-12);
+    }
+}
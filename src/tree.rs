@@ -2,41 +2,178 @@ use core::f64;
 use std::{
     collections::HashMap,
     ops::{Deref, Index, IndexMut},
+    sync::atomic::{AtomicUsize, Ordering},
 };
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{classic, conditional, histogram, oblique};
+
 const MIN_ELEMENTS_TO_SPLIT: usize = 3;
 const MAX_DEPTH: usize = 7;
+const MIN_ELEMENTS_PER_LEAF: usize = 1;
+const SIGNIFICANCE: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SplitSearch {
+    // 99 evenly spaced thresholds per axis at every node
+    #[default]
+    Exact,
+    // thresholds from per-bin class histograms, each axis is quantized once
+    // into at most `max_bins` (up to 256) quantile bins
+    Histogram {
+        max_bins: usize,
+    },
+    // the exact search, then the linear discriminant of each class against
+    // the others at the node, kept when it splits better than any single axis
+    Oblique,
+}
+
+impl SplitSearch {
+    // a histogram needs between 2 and 256 bins, training panics otherwise
+    pub fn is_valid(self) -> bool {
+        match self {
+            SplitSearch::Histogram { max_bins } => (2..=histogram::MAX_BINS).contains(&max_bins),
+            SplitSearch::Exact | SplitSearch::Oblique => true,
+        }
+    }
+}
+
+// the tree learners, all but CART ignore `split_search` and `ccp_alpha`, ID3
+// and C4.5 always use entropy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Algorithm {
+    // binary splits minimizing `impurity`, cost-complexity pruning
+    #[default]
+    Cart,
+    // one child per category of an axis, chosen by information gain, each
+    // axis used once per path; axes with many values are cut into quantile bins
+    Id3,
+    // binary thresholds chosen by gain ratio, pessimistic-error pruning
+    C45,
+    // conditional inference: the axis is the one most associated with the
+    // class by a χ² test, growth stops when no test is significant at
+    // `significance`; the threshold on that axis minimizes `impurity`
+    Conditional,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Impurity {
+    #[default]
+    Gini,
+    Entropy,
+}
+
+impl Impurity {
+    pub(crate) fn loss(self, counts: &[usize], len: usize) -> f64 {
+        match self {
+            Impurity::Gini => gini_loss(counts, len),
+            Impurity::Entropy => entropy_loss(counts, len),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hyperparameters {
+    pub max_depth: usize,
+    pub min_elements_to_split: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub split_search: SplitSearch,
+    // splits leaving fewer points than this on either side are not considered
+    #[cfg_attr(feature = "serde", serde(default = "default_min_elements_per_leaf"))]
+    pub min_elements_per_leaf: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub impurity: Impurity,
+    // cost-complexity pruning: a split is kept only if it lowers the weighted
+    // impurity of the tree by more than `ccp_alpha` per extra leaf, 0 disables it
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ccp_alpha: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub algorithm: Algorithm,
+    // the Šidák adjusted p-value a split needs with `Algorithm::Conditional`
+    #[cfg_attr(feature = "serde", serde(default = "default_significance"))]
+    pub significance: f64,
+}
+
+#[cfg(feature = "serde")]
+fn default_min_elements_per_leaf() -> usize {
+    MIN_ELEMENTS_PER_LEAF
+}
+
+#[cfg(feature = "serde")]
+fn default_significance() -> f64 {
+    SIGNIFICANCE
+}
+
+impl Default for Hyperparameters {
+    fn default() -> Self {
+        Self {
+            max_depth: MAX_DEPTH,
+            min_elements_to_split: MIN_ELEMENTS_TO_SPLIT,
+            split_search: SplitSearch::Exact,
+            min_elements_per_leaf: MIN_ELEMENTS_PER_LEAF,
+            impurity: Impurity::Gini,
+            ccp_alpha: 0.,
+            algorithm: Algorithm::Cart,
+            significance: SIGNIFICANCE,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Axis(pub i32);
 
+pub type LabelledData = Vec<(DataPoint, Class)>;
+
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Criterion {
     pub axis: Axis,
     pub split: f64,
+    // of the test that chose the axis, with `Algorithm::Conditional`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub p_value: Option<f64>,
 }
 
 impl Criterion {
-    fn split(
-        &self,
-        data: &Vec<(DataPoint, Class)>,
-    ) -> (Vec<(DataPoint, Class)>, Vec<(DataPoint, Class)>) {
+    fn classify(&self, data: &Vec<DataPoint>) -> (Vec<DataPoint>, Vec<DataPoint>) {
         let mut left = Vec::new();
         let mut right = Vec::new();
-        for (dp, class) in data {
+        for dp in data {
             if dp[self.axis] < self.split {
-                left.push((dp.clone(), *class));
+                left.push(dp.clone());
             } else {
-                right.push((dp.clone(), *class));
+                right.push(dp.clone());
             }
         }
         (left, right)
     }
+}
+
+// w·x < split, `weights` lists the axes with a non-zero weight
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hyperplane {
+    pub weights: Vec<(Axis, f64)>,
+    pub split: f64,
+}
+
+impl Hyperplane {
+    pub fn value(&self, dp: &DataPoint) -> f64 {
+        self.weights.iter().map(|(axis, w)| w * dp[axis]).sum()
+    }
+
     fn classify(&self, data: &Vec<DataPoint>) -> (Vec<DataPoint>, Vec<DataPoint>) {
         let mut left = Vec::new();
         let mut right = Vec::new();
         for dp in data {
-            if dp[self.axis] < self.split {
+            if self.value(dp) < self.split {
                 left.push(dp.clone());
             } else {
                 right.push(dp.clone());
@@ -46,7 +183,33 @@ impl Criterion {
     }
 }
 
+// one child per interval of `axis`, child i holding
+// thresholds[i - 1] <= dp[axis] < thresholds[i]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Multiway {
+    pub axis: Axis,
+    pub thresholds: Vec<f64>,
+}
+
+impl Multiway {
+    // missing values go to the last child, like they go right at a binary split
+    pub fn child(&self, value: f64) -> usize {
+        self.thresholds
+            .iter()
+            .position(|threshold| value < *threshold)
+            .unwrap_or(self.thresholds.len())
+    }
+
+    // the bounds of child `i`, lower <= dp[axis] < upper
+    pub fn interval(&self, i: usize) -> (Option<f64>, Option<f64>) {
+        let lower = i.checked_sub(1).map(|i| self.thresholds[i]);
+        (lower, self.thresholds.get(i).copied())
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Class(pub i32);
 
 impl Deref for Class {
@@ -58,6 +221,7 @@ impl Deref for Class {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DataPoint(pub Vec<f64>);
 
 impl Index<Axis> for DataPoint {
@@ -97,122 +261,538 @@ impl Deref for DataPoint {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DecisionTree {
     Branch(Criterion, Box<DecisionTree>, Box<DecisionTree>),
+    Oblique(Hyperplane, Box<DecisionTree>, Box<DecisionTree>),
+    Multiway(Multiway, Vec<DecisionTree>),
     Leave(Class),
 }
 
-fn gini_loss(classes: &[Class], vec: &[(DataPoint, Class)]) -> f64 {
+// column-major copy of the training data, nodes refer to rows by index so
+// splitting only reorders an index slice instead of cloning data points
+pub(crate) struct Columns {
+    pub(crate) axises: Vec<Axis>,
+    pub(crate) columns: Vec<Vec<f64>>,
+    // position of each row's class in `classes`
+    pub(crate) labels: Vec<usize>,
+    pub(crate) classes: Vec<Class>,
+}
+
+impl Columns {
+    pub(crate) fn new(axises: &[Axis], classes: &[Class], data: &[(DataPoint, Class)]) -> Self {
+        Columns {
+            axises: axises.to_vec(),
+            columns: axises
+                .iter()
+                .map(|axis| data.iter().map(|(dp, _class)| dp[axis]).collect())
+                .collect(),
+            labels: data
+                .iter()
+                .map(|(_dp, class)| classes.iter().position(|c| c == class).unwrap())
+                .collect(),
+            classes: classes.to_vec(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub(crate) fn counts(&self, indices: &[usize]) -> Vec<usize> {
+        let mut counts = vec![0; self.classes.len()];
+        for i in indices {
+            counts[self.labels[*i]] += 1;
+        }
+        counts
+    }
+
+    pub(crate) fn majority(&self, indices: &[usize]) -> Class {
+        let counts = self.counts(indices);
+        let mut best = 0;
+        for (i, count) in counts.iter().enumerate() {
+            if *count > counts[best] {
+                best = i;
+            }
+        }
+        self.classes[best]
+    }
+
+    // moves the rows going left to the front, returns how many there are
+    fn partition_by(&self, indices: &mut [usize], goes_left: impl Fn(usize) -> bool) -> usize {
+        let mut mid = 0;
+        for i in 0..indices.len() {
+            if goes_left(indices[i]) {
+                indices.swap(i, mid);
+                mid += 1;
+            }
+        }
+        mid
+    }
+
+    pub(crate) fn partition(&self, indices: &mut [usize], column: usize, split: f64) -> usize {
+        let values = &self.columns[column];
+        self.partition_by(indices, |row| values[row] < split)
+    }
+
+    // orders the rows by child, returns how many go to each one
+    pub(crate) fn partition_multiway(
+        &self,
+        indices: &mut [usize],
+        multiway: &Multiway,
+    ) -> Vec<usize> {
+        let values = &self.columns[self.column(multiway.axis)];
+        indices.sort_by_key(|row| multiway.child(values[*row]));
+        let mut lens = vec![0; multiway.thresholds.len() + 1];
+        for row in indices.iter() {
+            lens[multiway.child(values[*row])] += 1;
+        }
+        lens
+    }
+
+    pub(crate) fn column(&self, axis: Axis) -> usize {
+        self.axises.iter().position(|a| *a == axis).unwrap()
+    }
+
+    // w·x of `row` summed in the same order as `Hyperplane::value`, with
+    // `weights` given by column
+    pub(crate) fn project(&self, row: usize, weights: &[(usize, f64)]) -> f64 {
+        weights
+            .iter()
+            .map(|(column, w)| w * self.columns[*column][row])
+            .sum()
+    }
+
+    pub(crate) fn partition_oblique(
+        &self,
+        indices: &mut [usize],
+        hyperplane: &Hyperplane,
+    ) -> usize {
+        let weights: Vec<(usize, f64)> = hyperplane
+            .weights
+            .iter()
+            .map(|(axis, w)| (self.column(*axis), *w))
+            .collect();
+        self.partition_by(indices, |row| {
+            self.project(row, &weights) < hyperplane.split
+        })
+    }
+}
+
+fn gini_loss(counts: &[usize], len: usize) -> f64 {
     let mut loss = 0f64;
-    for class in classes {
-        let p = 1f64 / (vec.len() as f64)
-            * (vec.iter().filter(|(_dp, cls)| cls == class).count() as f64);
+    for count in counts {
+        let p = 1f64 / (len as f64) * (*count as f64);
         loss += p * (1f64 - p);
     }
     loss
 }
 
-fn find_criterion(axises: &[Axis], classes: &[Class], data: &Vec<(DataPoint, Class)>) -> Criterion {
-    let mut min_impurity = f64::MAX;
-    let mut min_impurity_axis = axises[0];
-    let mut min_impurity_split = 0f64;
-    for axis in axises {
-        let mut max = f64::MIN;
-        let mut min = f64::MAX;
-        for (dp, _class) in data {
-            max = max.max(dp[axis]);
-            min = min.min(dp[axis]);
-        }
-        let step = (max - min) / 100.;
-        if step == 0. {
+fn entropy_loss(counts: &[usize], len: usize) -> f64 {
+    let mut loss = 0f64;
+    for count in counts {
+        if *count > 0 {
+            let p = 1f64 / (len as f64) * (*count as f64);
+            loss -= p * p.log2();
+        }
+    }
+    loss
+}
+
+// calls `visit(split, impurity)` for every candidate on `column` that leaves
+// at least `min_elements_per_leaf` points on both sides; candidates are 99
+// evenly spaced thresholds between min and max, found in one sweep over the
+// sorted values
+pub(crate) fn sweep_splits(
+    columns: &Columns,
+    column: usize,
+    indices: &[usize],
+    hyperparameters: &Hyperparameters,
+    visit: impl FnMut(f64, f64),
+) {
+    let values: Vec<(f64, usize)> = indices
+        .iter()
+        .map(|i| (columns.columns[column][*i], columns.labels[*i]))
+        .collect();
+    sweep_values(columns, values, indices, hyperparameters, visit);
+}
+
+// the sweep of `sweep_splits` over any (value, label) of the rows in `indices`
+pub(crate) fn sweep_values(
+    columns: &Columns,
+    values: Vec<(f64, usize)>,
+    indices: &[usize],
+    hyperparameters: &Hyperparameters,
+    mut visit: impl FnMut(f64, f64),
+) {
+    sweep_counts(
+        columns,
+        values,
+        indices,
+        hyperparameters,
+        |split, left, left_len, right, right_len| {
+            let len = (left_len + right_len) as f64;
+            let left_loss = hyperparameters.impurity.loss(left, left_len);
+            let right_loss = hyperparameters.impurity.loss(right, right_len);
+            let impurity =
+                (left_len as f64 / len) * left_loss + (right_len as f64 / len) * right_loss;
+            visit(split, impurity);
+        },
+    );
+}
+
+// the same candidates as `sweep_values`, calling
+// `visit(split, left counts, left len, right counts, right len)`
+pub(crate) fn sweep_counts(
+    columns: &Columns,
+    mut values: Vec<(f64, usize)>,
+    indices: &[usize],
+    hyperparameters: &Hyperparameters,
+    mut visit: impl FnMut(f64, &[usize], usize, &[usize], usize),
+) {
+    let min_len = hyperparameters.min_elements_per_leaf.max(1);
+    values.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut max = f64::MIN;
+    let mut min = f64::MAX;
+    for (value, _label) in &values {
+        max = max.max(*value);
+        min = min.min(*value);
+    }
+    let step = (max - min) / 100.;
+    if step == 0. {
+        return;
+    }
+    let total = columns.counts(indices);
+    let mut left = vec![0; total.len()];
+    let mut right = total.clone();
+    let mut left_len = 0;
+    let mut split_criterion = min + step;
+    while split_criterion <= max {
+        while left_len < values.len() && values[left_len].0 < split_criterion {
+            left[values[left_len].1] += 1;
+            right[values[left_len].1] -= 1;
+            left_len += 1;
+        }
+        let right_len = values.len() - left_len;
+        if left_len < min_len || right_len < min_len {
+            split_criterion += step;
             continue;
         }
-        let mut split_criterion = min + step;
-        while split_criterion <= max {
-            let c = Criterion {
+        visit(split_criterion, &left, left_len, &right, right_len);
+        split_criterion += step;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImpurityCurve {
+    pub axis: Axis,
+    // (threshold, weighted impurity) of every candidate the search tries
+    pub points: Vec<(f64, f64)>,
+}
+
+pub(crate) fn curves(
+    columns: &Columns,
+    indices: &[usize],
+    hyperparameters: &Hyperparameters,
+) -> Vec<ImpurityCurve> {
+    columns
+        .axises
+        .iter()
+        .enumerate()
+        .map(|(column, axis)| {
+            let mut points = Vec::new();
+            sweep_splits(
+                columns,
+                column,
+                indices,
+                hyperparameters,
+                |split, impurity| points.push((split, impurity)),
+            );
+            ImpurityCurve {
                 axis: *axis,
-                split: split_criterion,
-            };
-            let (left, right) = c.split(data);
-            if left.is_empty() || right.is_empty() {
-                split_criterion += step;
-                continue;
+                points,
             }
-            let left_loss = gini_loss(classes, &left);
-            let right_loss = gini_loss(classes, &right);
-            let impurity = ((left.len() as f64) / (data.len() as f64)) * left_loss
-                + ((right.len() as f64) / (data.len() as f64)) * right_loss;
+        })
+        .collect()
+}
+
+// the impurity of every candidate split of `data`, per axis, as seen by the
+// exact search at a node holding `data`
+pub fn impurity_curves(
+    axises: &[Axis],
+    classes: &[Class],
+    data: &[(DataPoint, Class)],
+    hyperparameters: &Hyperparameters,
+) -> Vec<ImpurityCurve> {
+    let columns = Columns::new(axises, classes, data);
+    let indices: Vec<usize> = (0..columns.len()).collect();
+    curves(&columns, &indices, hyperparameters)
+}
+
+// returns the lowest (impurity, split) found on `column`, if it can be split at all
+pub(crate) fn find_split(
+    columns: &Columns,
+    column: usize,
+    indices: &[usize],
+    hyperparameters: &Hyperparameters,
+) -> Option<(f64, f64)> {
+    let mut best = None;
+    let mut min_impurity = f64::MAX;
+    sweep_splits(
+        columns,
+        column,
+        indices,
+        hyperparameters,
+        |split, impurity| {
             if impurity < min_impurity {
                 min_impurity = impurity;
-                min_impurity_axis = *axis;
-                min_impurity_split = split_criterion;
+                best = Some((impurity, split));
+            }
+        },
+    );
+    best
+}
+
+// returns the column and threshold of the best split, `None` if no axis can be split
+pub(crate) fn find_criterion(
+    columns: &Columns,
+    indices: &[usize],
+    hyperparameters: &Hyperparameters,
+) -> Option<(usize, f64)> {
+    best_criterion(columns, indices, hyperparameters)
+        .map(|(_impurity, column, split)| (column, split))
+}
+
+// (impurity, column, threshold) of the best axis-aligned split
+fn best_criterion(
+    columns: &Columns,
+    indices: &[usize],
+    hyperparameters: &Hyperparameters,
+) -> Option<(f64, usize, f64)> {
+    #[cfg(feature = "parallel")]
+    let splits: Vec<_> = (0..columns.axises.len())
+        .into_par_iter()
+        .map(|column| find_split(columns, column, indices, hyperparameters))
+        .collect();
+    #[cfg(not(feature = "parallel"))]
+    let splits: Vec<_> = (0..columns.axises.len())
+        .map(|column| find_split(columns, column, indices, hyperparameters))
+        .collect();
+    // ties go to the first axis, the same with or without `parallel`
+    let mut min_impurity = f64::MAX;
+    let mut best = None;
+    for (column, split) in splits.into_iter().enumerate() {
+        if let Some((impurity, split)) = split {
+            if impurity < min_impurity {
+                min_impurity = impurity;
+                best = Some((impurity, column, split));
             }
-            split_criterion += step;
         }
     }
-    Criterion {
-        axis: min_impurity_axis,
-        split: min_impurity_split,
-    }
+    best
 }
 
-#[derive(Debug, Clone)]
-enum TrainTree {
-    Branch(Criterion, Box<TrainTree>, Box<TrainTree>),
-    Leave(Vec<(DataPoint, Class)>),
-}
-
-impl From<TrainTree> for DecisionTree {
-    fn from(value: TrainTree) -> Self {
-        match value {
-            TrainTree::Branch(criterion, train_tree, train_tree1) => DecisionTree::Branch(
-                criterion,
-                Box::new(DecisionTree::from(*train_tree)),
-                Box::new(DecisionTree::from(*train_tree1)),
-            ),
-            TrainTree::Leave(vec) => {
-                let mut counter = HashMap::<Class, usize>::new();
-                for (_dp, class) in vec {
-                    match counter.get_mut(&class) {
-                        Some(c) => *c += 1,
-                        None => {
-                            counter.insert(class, 1);
-                        }
-                    }
-                }
-                DecisionTree::Leave(
-                    *counter
-                        .iter()
-                        .max_by_key(|(_class, count)| *count)
-                        .unwrap()
-                        .0,
-                )
-            }
+pub(crate) enum Split {
+    Axis(usize, f64),
+    Oblique(Hyperplane),
+}
+
+// the best split of the exact search, or of the oblique one with `SplitSearch::Oblique`
+fn find_node_split(
+    columns: &Columns,
+    indices: &[usize],
+    hyperparameters: &Hyperparameters,
+) -> Option<Split> {
+    let axis = best_criterion(columns, indices, hyperparameters);
+    if hyperparameters.split_search == SplitSearch::Oblique {
+        let min_impurity = axis.map_or(f64::MAX, |(impurity, _column, _split)| impurity);
+        if let Some((_impurity, hyperplane)) =
+            oblique::find_hyperplane(columns, indices, hyperparameters)
+                .filter(|(impurity, _hyperplane)| *impurity < min_impurity)
+        {
+            return Some(Split::Oblique(hyperplane));
         }
     }
+    axis.map(|(_impurity, column, split)| Split::Axis(column, split))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Progress {
+    pub nodes: usize,
+    pub depth: usize,
+    // points that have reached a leaf out of all training points
+    pub settled: usize,
+    pub total: usize,
+}
+
+pub(crate) struct Tracker<'a> {
+    nodes: AtomicUsize,
+    settled: AtomicUsize,
+    total: usize,
+    on_progress: &'a (dyn Fn(Progress) + Sync),
+}
+
+impl Tracker<'_> {
+    pub(crate) fn grown(&self, depth: usize, settled: usize) {
+        let nodes = self.nodes.fetch_add(1, Ordering::Relaxed) + 1;
+        let settled = self.settled.fetch_add(settled, Ordering::Relaxed) + settled;
+        (self.on_progress)(Progress {
+            nodes,
+            depth,
+            settled,
+            total: self.total,
+        });
+    }
 }
 
 fn grow(
-    axises: &[Axis],
-    classes: &[Class],
-    data: Vec<(DataPoint, Class)>,
+    columns: &Columns,
+    indices: &mut [usize],
     depth: usize,
-) -> TrainTree {
-    if depth < MAX_DEPTH && data.len() > MIN_ELEMENTS_TO_SPLIT {
-        let c = find_criterion(axises, classes, &data);
-        let (left, right) = c.split(&data);
-        TrainTree::Branch(
-            c,
-            Box::new(grow(axises, classes, left, depth + 1)),
-            Box::new(grow(axises, classes, right, depth + 1)),
-        )
+    hyperparameters: &Hyperparameters,
+    tracker: &Tracker,
+) -> DecisionTree {
+    if depth < hyperparameters.max_depth && indices.len() > hyperparameters.min_elements_to_split {
+        if let Some(split) = find_node_split(columns, indices, hyperparameters) {
+            let mid = match &split {
+                Split::Axis(column, split) => columns.partition(indices, *column, *split),
+                Split::Oblique(hyperplane) => columns.partition_oblique(indices, hyperplane),
+            };
+            let (left, right) = indices.split_at_mut(mid);
+            tracker.grown(depth, 0);
+            #[cfg(feature = "parallel")]
+            let (left, right) = rayon::join(
+                || grow(columns, left, depth + 1, hyperparameters, tracker),
+                || grow(columns, right, depth + 1, hyperparameters, tracker),
+            );
+            #[cfg(not(feature = "parallel"))]
+            let (left, right) = (
+                grow(columns, left, depth + 1, hyperparameters, tracker),
+                grow(columns, right, depth + 1, hyperparameters, tracker),
+            );
+            return match split {
+                Split::Axis(column, split) => {
+                    let c = Criterion {
+                        axis: columns.axises[column],
+                        split,
+                        p_value: None,
+                    };
+                    DecisionTree::Branch(c, Box::new(left), Box::new(right))
+                }
+                Split::Oblique(hyperplane) => {
+                    DecisionTree::Oblique(hyperplane, Box::new(left), Box::new(right))
+                }
+            };
+        }
+    }
+    tracker.grown(depth, indices.len());
+    DecisionTree::Leave(columns.majority(indices))
+}
+
+// cost-complexity pruning bottom-up: a subtree becomes a leaf when that costs
+// no more than keeping it, with the cost of a tree being the impurity of its
+// leaves weighted by their share of all points plus `alpha` per leaf; returns
+// the pruned tree and its cost
+fn prune(
+    columns: &Columns,
+    indices: &mut [usize],
+    tree: DecisionTree,
+    hyperparameters: &Hyperparameters,
+) -> (DecisionTree, f64) {
+    let counts = columns.counts(indices);
+    let leaf_cost = hyperparameters.impurity.loss(&counts, indices.len()) * indices.len() as f64
+        / columns.len() as f64
+        + hyperparameters.ccp_alpha;
+    let lens = match &tree {
+        DecisionTree::Branch(c, _, _) => {
+            let mid = columns.partition(indices, columns.column(c.axis), c.split);
+            vec![mid, indices.len() - mid]
+        }
+        DecisionTree::Oblique(hyperplane, _, _) => {
+            let mid = columns.partition_oblique(indices, hyperplane);
+            vec![mid, indices.len() - mid]
+        }
+        DecisionTree::Multiway(multiway, _) => columns.partition_multiway(indices, multiway),
+        DecisionTree::Leave(_) => return (tree, leaf_cost),
+    };
+    let mut cost = 0.;
+    let mut rest = &mut indices[..];
+    let mut lens = lens.into_iter();
+    let mut prune_subtree = |subtree: DecisionTree| {
+        let (child, others) = std::mem::take(&mut rest).split_at_mut(lens.next().unwrap());
+        rest = others;
+        let (subtree, subtree_cost) = prune(columns, child, subtree, hyperparameters);
+        cost += subtree_cost;
+        subtree
+    };
+    let tree = match tree {
+        DecisionTree::Branch(c, left_tree, right_tree) => {
+            let left_tree = prune_subtree(*left_tree);
+            let right_tree = prune_subtree(*right_tree);
+            DecisionTree::Branch(c, Box::new(left_tree), Box::new(right_tree))
+        }
+        DecisionTree::Oblique(hyperplane, left_tree, right_tree) => {
+            let left_tree = prune_subtree(*left_tree);
+            let right_tree = prune_subtree(*right_tree);
+            DecisionTree::Oblique(hyperplane, Box::new(left_tree), Box::new(right_tree))
+        }
+        DecisionTree::Multiway(multiway, subtrees) => {
+            DecisionTree::Multiway(multiway, subtrees.into_iter().map(prune_subtree).collect())
+        }
+        DecisionTree::Leave(_) => unreachable!(),
+    };
+    if leaf_cost <= cost {
+        (DecisionTree::Leave(columns.majority(indices)), leaf_cost)
     } else {
-        TrainTree::Leave(data)
+        (tree, cost)
     }
 }
 
-pub fn train(axises: &[Axis], classes: &[Class], vec: Vec<(DataPoint, Class)>) -> DecisionTree {
-    grow(axises, classes, vec, 0).into()
+pub fn train(
+    axises: &[Axis],
+    classes: &[Class],
+    vec: Vec<(DataPoint, Class)>,
+    hyperparameters: &Hyperparameters,
+) -> DecisionTree {
+    train_with_progress(axises, classes, vec, hyperparameters, &|_| {})
+}
+
+// `on_progress` is called after every node, from rayon threads with `parallel`
+pub fn train_with_progress(
+    axises: &[Axis],
+    classes: &[Class],
+    vec: Vec<(DataPoint, Class)>,
+    hyperparameters: &Hyperparameters,
+    on_progress: &(dyn Fn(Progress) + Sync),
+) -> DecisionTree {
+    let columns = Columns::new(axises, classes, &vec);
+    drop(vec);
+    let tracker = Tracker {
+        nodes: AtomicUsize::new(0),
+        settled: AtomicUsize::new(0),
+        total: columns.len(),
+        on_progress,
+    };
+    let mut indices: Vec<usize> = (0..columns.len()).collect();
+    let tree = match (hyperparameters.algorithm, hyperparameters.split_search) {
+        (Algorithm::Id3, _) => return classic::train_id3(&columns, hyperparameters, &tracker),
+        (Algorithm::C45, _) => return classic::train_c45(&columns, hyperparameters, &tracker),
+        (Algorithm::Conditional, _) => {
+            return conditional::train(&columns, hyperparameters, &tracker)
+        }
+        (Algorithm::Cart, SplitSearch::Exact | SplitSearch::Oblique) => {
+            grow(&columns, &mut indices, 0, hyperparameters, &tracker)
+        }
+        (Algorithm::Cart, SplitSearch::Histogram { max_bins }) => {
+            histogram::train(&columns, max_bins, hyperparameters, &tracker)
+        }
+    };
+    if hyperparameters.ccp_alpha > 0. {
+        prune(&columns, &mut indices, tree, hyperparameters).0
+    } else {
+        tree
+    }
 }
 
 fn merge_result(
@@ -238,6 +818,24 @@ pub fn classify(tree: &DecisionTree, data: Vec<DataPoint>) -> HashMap<Class, Vec
             let right = classify(right_tree, right_data);
             merge_result(left, right)
         }
+        DecisionTree::Oblique(hyperplane, left_tree, right_tree) => {
+            let (left_data, right_data) = hyperplane.classify(&data);
+            let left = classify(left_tree, left_data);
+            let right = classify(right_tree, right_data);
+            merge_result(left, right)
+        }
+        DecisionTree::Multiway(multiway, subtrees) => {
+            let mut groups = vec![Vec::new(); subtrees.len()];
+            for dp in data {
+                groups[multiway.child(dp[multiway.axis])].push(dp);
+            }
+            subtrees
+                .iter()
+                .zip(groups)
+                .map(|(subtree, group)| classify(subtree, group))
+                .reduce(merge_result)
+                .unwrap()
+        }
         DecisionTree::Leave(class) => {
             let mut result = HashMap::new();
             result.insert(*class, data);
@@ -245,3 +843,47 @@ pub fn classify(tree: &DecisionTree, data: Vec<DataPoint>) -> HashMap<Class, Vec
         }
     }
 }
+
+pub fn predict(tree: &DecisionTree, dp: &DataPoint) -> Class {
+    match tree {
+        DecisionTree::Branch(c, left_tree, right_tree) => {
+            if dp[c.axis] < c.split {
+                predict(left_tree, dp)
+            } else {
+                predict(right_tree, dp)
+            }
+        }
+        DecisionTree::Oblique(hyperplane, left_tree, right_tree) => {
+            if hyperplane.value(dp) < hyperplane.split {
+                predict(left_tree, dp)
+            } else {
+                predict(right_tree, dp)
+            }
+        }
+        DecisionTree::Multiway(multiway, subtrees) => {
+            predict(&subtrees[multiway.child(dp[multiway.axis])], dp)
+        }
+        DecisionTree::Leave(class) => *class,
+    }
+}
+
+pub fn leaves(tree: &DecisionTree) -> usize {
+    match tree {
+        DecisionTree::Branch(_, left, right) | DecisionTree::Oblique(_, left, right) => {
+            leaves(left) + leaves(right)
+        }
+        DecisionTree::Multiway(_, subtrees) => subtrees.iter().map(leaves).sum(),
+        DecisionTree::Leave(_) => 1,
+    }
+}
+
+// the number of splits on the longest path, 0 for a single leaf
+pub fn depth(tree: &DecisionTree) -> usize {
+    match tree {
+        DecisionTree::Branch(_, left, right) | DecisionTree::Oblique(_, left, right) => {
+            1 + depth(left).max(depth(right))
+        }
+        DecisionTree::Multiway(_, subtrees) => 1 + subtrees.iter().map(depth).max().unwrap_or(0),
+        DecisionTree::Leave(_) => 0,
+    }
+}
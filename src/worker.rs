@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{DedicatedWorkerGlobalScope, MessageEvent, Worker};
+
+use crate::eval::{learning_curve, validation_curve, CurveKind, CurvePoint};
+use crate::search::{search_with_progress, SearchReport};
+use crate::tree::{
+    train_with_progress, Axis, Class, DecisionTree, Hyperparameters, LabelledData, Progress,
+};
+
+// loader shim generated by trunk for the `dectree-worker` binary
+const WORKER_URL: &str = "./dectree-worker_loader.js";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainRequest {
+    pub id: u64,
+    pub axises: Vec<Axis>,
+    pub classes: Vec<Class>,
+    pub data: LabelledData,
+    pub hyperparameters: Hyperparameters,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchRequest {
+    pub id: u64,
+    pub axises: Vec<Axis>,
+    pub classes: Vec<Class>,
+    pub data: LabelledData,
+    pub candidates: Vec<Hyperparameters>,
+    pub folds: usize,
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurveRequest {
+    pub id: u64,
+    pub kind: CurveKind,
+    pub axises: Vec<Axis>,
+    pub classes: Vec<Class>,
+    pub train_data: LabelledData,
+    pub test_data: LabelledData,
+    pub seed: u64,
+    pub hyperparameters: Hyperparameters,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum WorkerRequest {
+    Train(TrainRequest),
+    Search(SearchRequest),
+    Curve(CurveRequest),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TrainEvent {
+    Progress { id: u64, progress: Progress },
+    Trained { id: u64, tree: DecisionTree },
+    Searching { id: u64, done: usize, total: usize },
+    Searched { id: u64, report: SearchReport },
+    Drawing { id: u64, done: usize, total: usize },
+    Drawn { id: u64, points: Vec<CurvePoint> },
+}
+
+// messages are passed as JSON strings, serde_json round-trips f64 exactly
+fn decode<T: for<'de> Deserialize<'de>>(ev: &MessageEvent) -> T {
+    serde_json::from_str(&ev.data().as_string().unwrap()).unwrap()
+}
+
+fn encode<T: Serialize>(value: &T) -> JsValue {
+    serde_json::to_string(value).unwrap().into()
+}
+
+// the callbacks must be `Sync`, so they look the scope up instead of capturing it
+fn post_event(event: &TrainEvent) {
+    let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+    scope.post_message(&encode(event)).unwrap();
+}
+
+// entry point of the worker binary
+pub fn run_worker() {
+    let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+    let reply = scope.clone();
+    let onmessage = Closure::<dyn FnMut(MessageEvent)>::new(move |ev: MessageEvent| {
+        let event = match decode(&ev) {
+            WorkerRequest::Train(request) => {
+                let id = request.id;
+                let tree = train_with_progress(
+                    &request.axises,
+                    &request.classes,
+                    request.data,
+                    &request.hyperparameters,
+                    &move |progress| post_event(&TrainEvent::Progress { id, progress }),
+                );
+                TrainEvent::Trained { id, tree }
+            }
+            WorkerRequest::Search(request) => {
+                let id = request.id;
+                let total = request.candidates.len();
+                let report = search_with_progress(
+                    &request.axises,
+                    &request.classes,
+                    &request.data,
+                    &request.candidates,
+                    request.folds,
+                    request.seed,
+                    &move |done| post_event(&TrainEvent::Searching { id, done, total }),
+                );
+                TrainEvent::Searched { id, report }
+            }
+            WorkerRequest::Curve(request) => {
+                let id = request.id;
+                let xs = request.kind.xs();
+                let total = xs.len();
+                let points = xs
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, x)| {
+                        // one tree per point; the learning curve shuffles the same
+                        // way for every fraction, so the subsets still contain each other
+                        let point = match request.kind {
+                            CurveKind::Learning => learning_curve(
+                                &request.axises,
+                                &request.classes,
+                                &request.train_data,
+                                &request.test_data,
+                                &[x],
+                                request.seed,
+                                &request.hyperparameters,
+                            ),
+                            CurveKind::Validation(parameter) => validation_curve(
+                                &request.axises,
+                                &request.classes,
+                                &request.train_data,
+                                &request.test_data,
+                                parameter,
+                                &[x as usize],
+                                &request.hyperparameters,
+                            ),
+                        }[0];
+                        post_event(&TrainEvent::Drawing {
+                            id,
+                            done: i + 1,
+                            total,
+                        });
+                        point
+                    })
+                    .collect();
+                TrainEvent::Drawn { id, points }
+            }
+        };
+        reply.post_message(&encode(&event)).unwrap();
+    });
+    scope.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+    onmessage.forget();
+}
+
+// training is synchronous inside the worker, so cancelling means terminating
+// the worker and spawning a fresh one
+pub fn spawn_worker(on_event: impl Fn(TrainEvent) + 'static) -> Worker {
+    let worker = Worker::new(WORKER_URL).unwrap();
+    let onmessage = Closure::<dyn FnMut(MessageEvent)>::new(move |ev: MessageEvent| {
+        on_event(decode(&ev));
+    });
+    worker.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+    onmessage.forget();
+    worker
+}
+
+pub fn post_train(worker: &Worker, request: TrainRequest) {
+    worker
+        .post_message(&encode(&WorkerRequest::Train(request)))
+        .unwrap();
+}
+
+pub fn post_curve(worker: &Worker, request: CurveRequest) {
+    worker
+        .post_message(&encode(&WorkerRequest::Curve(request)))
+        .unwrap();
+}
+
+pub fn post_search(worker: &Worker, request: SearchRequest) {
+    worker
+        .post_message(&encode(&WorkerRequest::Search(request)))
+        .unwrap();
+}